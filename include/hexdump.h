@@ -0,0 +1,37 @@
+#ifndef HEXDUMP_H
+#define HEXDUMP_H
+
+/* C interface of the hexdump crate, built with the `ffi` feature. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef void (*hexdump_line_callback)(const char *line, size_t len, void *user);
+
+/*
+ * Formats a single data line showing up to 16 bytes at `offset`.
+ *
+ * Writes the NUL-terminated line to `out` if it fits into `out_len` bytes.
+ * Returns the length of the line, excluding the terminating NUL, like
+ * `snprintf`. Bytes beyond the first 16 are ignored.
+ */
+size_t hexdump_line(const uint8_t *data, size_t len, size_t offset, char *out, size_t out_len);
+
+/*
+ * Calls `callback` for each line of the hexdump of the given bytes.
+ *
+ * The line passed to the callback is NUL-terminated and only valid for the
+ * duration of the call. `user` is passed through unchanged. Returns 0 on
+ * success and -1 without doing anything if `callback` is NULL.
+ */
+int hexdump_each(const uint8_t *data, size_t len, hexdump_line_callback callback, void *user);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
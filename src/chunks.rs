@@ -0,0 +1,124 @@
+use config::Config;
+use std::cmp;
+use std::iter;
+
+/// The bytes shown on one data line, see `hexdump_chunks`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Chunk<'a> {
+    /// Offset of the first byte as shown in the dump.
+    pub offset: usize,
+    /// The bytes of the line.
+    pub bytes: &'a [u8],
+}
+
+/// Return type of `hexdump_chunks`.
+///
+/// Yields the bytes of each data line without formatting them. Options
+/// that only change the output, like line limits or collapsed runs, don't
+/// apply.
+#[derive(Clone, Debug)]
+pub struct Chunks<'a> {
+    bytes: &'a [u8],
+    chunk_length: usize,
+    base_offset: usize,
+    front: usize,
+    back: usize,
+}
+
+/// Creates an iterator over the bytes of each line of a hexdump.
+///
+/// See `Config::hexdump_chunks` for details.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+/// use hexdump::Chunk;
+///
+/// let chunks: Vec<_> = hexdump::hexdump_chunks(b"0123456789abcdefXY").collect();
+/// assert_eq!(chunks, [
+///     Chunk { offset: 0, bytes: b"0123456789abcdef" },
+///     Chunk { offset: 16, bytes: b"XY" },
+/// ]);
+/// ```
+pub fn hexdump_chunks(bytes: &[u8]) -> Chunks<'_> {
+    Config::new().hexdump_chunks(bytes)
+}
+
+impl<'a> Chunks<'a> {
+    pub(crate) fn new(bytes: &'a [u8], config: &Config) -> Chunks<'a> {
+        let chunk_length = config.chunk_length();
+        // No offset is larger than the one of the end.
+        config.end_offset(bytes.len());
+        Chunks {
+            bytes,
+            chunk_length,
+            base_offset: config.base_offset,
+            front: 0,
+            back: bytes.len().div_ceil(chunk_length),
+        }
+    }
+    /// Returns the offset shown in the summary line.
+    pub fn end(&self) -> usize {
+        self.base_offset + self.bytes.len()
+    }
+    fn chunk(&self, index: usize) -> Chunk<'a> {
+        let start = index * self.chunk_length;
+        let end = cmp::min(start + self.chunk_length, self.bytes.len());
+        Chunk { offset: self.base_offset + start, bytes: &self.bytes[start..end] }
+    }
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = Chunk<'a>;
+    fn next(&mut self) -> Option<Chunk<'a>> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.chunk(self.front - 1))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
+    fn nth(&mut self, n: usize) -> Option<Chunk<'a>> {
+        self.front += cmp::min(n, self.len());
+        self.next()
+    }
+}
+
+impl<'a> DoubleEndedIterator for Chunks<'a> {
+    fn next_back(&mut self) -> Option<Chunk<'a>> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.chunk(self.back))
+    }
+}
+
+impl<'a> iter::FusedIterator for Chunks<'a> {}
+
+impl<'a> ExactSizeIterator for Chunks<'a> {
+    fn len(&self) -> usize {
+        self.back - self.front
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use config::Config;
+
+    quickcheck! {
+        fn chunks(bytes: Vec<u8>, base_offset: usize) -> bool {
+            let config = Config::new().base_offset(base_offset % 0x10000);
+            let lines: Vec<_> = config.hexdump_iter(&bytes).collect();
+            let chunks: Vec<_> = config.hexdump_chunks(&bytes).collect();
+            chunks.len() + 1 == lines.len()
+                && chunks.iter().zip(&lines)
+                    .all(|(c, l)| l.offset() == Some(c.offset) && l.data() == Some(c.bytes.to_vec()))
+                && lines.last().unwrap().offset() == Some(config.hexdump_chunks(&bytes).end())
+                && chunks.iter().rev().cloned().eq(config.hexdump_chunks(&bytes).rev())
+        }
+    }
+}
@@ -0,0 +1,1917 @@
+use chunks::Chunks;
+use console;
+use csv::Csv;
+use diff::Diff;
+use display::Dump;
+use display::DumpDiff;
+use ebcdic;
+use fallible::TryHexdump;
+use frames::Frames;
+use frames::IntoFrame;
+use hex::DecodeHexError;
+use hex::decode_hex;
+use highlight::Highlight;
+use imp::CHUNK_LENGTH;
+use imp::Hexdump;
+use imp::Input;
+use imp::Line;
+use imp::SliceWriter;
+use imp::line_width;
+use imp::sanitize_byte;
+use imp::write_line;
+use layout::Endian;
+use layout::Layout;
+use markdown;
+use overview::Overview;
+use plain::PLAIN_CHUNK_LENGTH;
+use plain::PlainHex;
+#[cfg(feature = "mmap")] use mmap;
+#[cfg(all(feature = "process", target_os = "linux"))] use process::ProcessMemory;
+use reader::HexdumpReader;
+use search::Search;
+use segments::Segments;
+use stream::Continuation;
+use stream::HexdumpStream;
+use stream::Live;
+use strings::Strings;
+use style::Style;
+use style::StyleFn;
+use style::Theme;
+use style;
+use trace::Trace;
+use watch::Watch;
+use wireshark::Wireshark;
+use writer::HexdumpWriter;
+use std::convert::TryFrom;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::io::IsTerminal;
+use std::ops;
+use std::path::Path;
+use std::sync::Arc;
+use std::io::Read;
+use std::io::Write;
+
+/// Line terminator used when writing a hexdump to an `io::Write` sink.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LineTerminator {
+    /// `"\n"`, the default.
+    #[default]
+    Lf,
+    /// `"\r\n"`.
+    CrLf,
+}
+
+impl LineTerminator {
+    /// Returns the terminator as a string.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineTerminator::Lf => "\n",
+            LineTerminator::CrLf => "\r\n",
+        }
+    }
+}
+
+/// Standard stream printed to by `Config::hexdump`, see `Config::sink`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Sink {
+    /// Standard output, the default.
+    #[default]
+    Stdout,
+    /// Standard error, which keeps diagnostic dumps out of machine-readable
+    /// output.
+    Stderr,
+}
+
+impl Sink {
+    fn is_terminal(self) -> bool {
+        match self {
+            Sink::Stdout => io::stdout().is_terminal(),
+            Sink::Stderr => io::stderr().is_terminal(),
+        }
+    }
+}
+
+/// Columns of the data lines showing the bytes, see `Config::columns`.
+///
+/// The offset column always comes last.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Columns {
+    /// The data column followed by the text column, the default.
+    #[default]
+    DataText,
+    /// The text column followed by the data column.
+    TextData,
+    /// Only the data column.
+    Data,
+    /// Only the text column.
+    Text,
+}
+
+/// Column of a data line showing the bytes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Column {
+    Data,
+    Text,
+}
+
+impl Columns {
+    /// Returns the shown columns from left to right.
+    pub(crate) fn order(self) -> &'static [Column] {
+        match self {
+            Columns::DataText => &[Column::Data, Column::Text],
+            Columns::TextData => &[Column::Text, Column::Data],
+            Columns::Data => &[Column::Data],
+            Columns::Text => &[Column::Text],
+        }
+    }
+    /// Returns whether the data column is shown.
+    pub(crate) fn has_data(self) -> bool {
+        self != Columns::Text
+    }
+}
+
+/// Whether escape sequences for colors and styles are written, see
+/// `Config::color`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ColorChoice {
+    /// Always write them.
+    Always,
+    /// Write them only when printing to a terminal, the default.
+    ///
+    /// `hexdump`, `try_hexdump` and `hexdump_file` check the stream
+    /// selected by `sink`. Everything else, like lines, strings, writers
+    /// and `dump_diff`, counts as not going to a terminal, unless the
+    /// choice is resolved by `Config::resolve_color` first.
+    ///
+    /// A non-empty `NO_COLOR` environment variable disables colors, and
+    /// `CLICOLOR_FORCE` set to anything but `0` enables them regardless.
+    /// Windows consoles are switched to interpreting escape sequences, and
+    /// colors are disabled for those that can't, i.e. those before
+    /// Windows 10.
+    #[default]
+    Auto,
+    /// Never write them, e.g. for log files.
+    Never,
+}
+
+/// Numeral system used to show the bytes, see `Config::byte_format`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ByteFormat {
+    /// Two lowercase hex digits per byte, the default.
+    #[default]
+    Hex,
+    /// Eight binary digits per byte.
+    Binary,
+    /// Three octal digits per byte, like `od -b`.
+    Octal,
+    /// Unsigned decimal number, right-aligned to three characters.
+    Decimal,
+    /// Signed decimal number, right-aligned to four characters.
+    SignedDecimal,
+}
+
+impl ByteFormat {
+    /// Returns the number of characters used to show a byte.
+    pub fn width(self) -> usize {
+        match self {
+            ByteFormat::Hex => 2,
+            ByteFormat::Binary => 8,
+            ByteFormat::Octal | ByteFormat::Decimal => 3,
+            ByteFormat::SignedDecimal => 4,
+        }
+    }
+    /// Returns the number of spaces between two bytes of the same group.
+    pub(crate) fn separator(self) -> usize {
+        match self {
+            ByteFormat::Hex => 0,
+            _ => 1,
+        }
+    }
+    /// Returns the number of bytes per line used unless configured
+    /// otherwise.
+    pub(crate) fn default_bytes_per_line(self) -> usize {
+        match self {
+            ByteFormat::Hex => CHUNK_LENGTH,
+            ByteFormat::Binary => 4,
+            _ => 8,
+        }
+    }
+    pub(crate) fn write<W: fmt::Write>(self, w: &mut W, byte: u8, uppercase: bool)
+        -> fmt::Result
+    {
+        match self {
+            ByteFormat::Hex if uppercase => write!(w, "{:02X}", byte),
+            ByteFormat::Hex => write!(w, "{:02x}", byte),
+            ByteFormat::Binary => write!(w, "{:08b}", byte),
+            ByteFormat::Octal => write!(w, "{:03o}", byte),
+            ByteFormat::Decimal => write!(w, "{:3}", byte),
+            ByteFormat::SignedDecimal => write!(w, "{:4}", byte as i8),
+        }
+    }
+}
+
+/// Character encoding of the text column, see `Config::text_encoding`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TextEncoding {
+    /// Printable ASCII characters, everything else as a dot. The default.
+    #[default]
+    Ascii,
+    /// UTF-16 code units of the given byte order.
+    ///
+    /// Each character is shown in the cell of its first byte, the other
+    /// cells it occupies are left blank. Code units are aligned to even
+    /// offsets.
+    Utf16(Endian),
+    /// EBCDIC code page 037, used on US and Canadian mainframes.
+    Cp037,
+    /// EBCDIC code page 500, the international variant of code page 037.
+    Cp500,
+}
+
+impl TextEncoding {
+    /// Writes the text column for `chunk`, which starts at `position`.
+    ///
+    /// Writes exactly one character per byte of `chunk`, except for wide
+    /// characters. Bytes that don't stand for a printable character are
+    /// written as `placeholder`, and bytes for which `redacted` returns true
+    /// as `*`. Characters standing for a byte are written using `cell`,
+    /// which gets the index of the byte. The columns taken up by the other
+    /// bytes of wide characters are passed to `cell` as `None`.
+    pub(crate) fn write<W, F, C>(self, w: &mut W, position: usize, chunk: &[u8],
+                                 placeholder: char, redacted: F, mut cell: C)
+        -> fmt::Result
+        where W: fmt::Write, F: Fn(usize) -> bool,
+              C: FnMut(&mut W, usize, Option<char>) -> fmt::Result,
+    {
+        match self {
+            TextEncoding::Ascii => {
+                for (i, &b) in chunk.iter().enumerate() {
+                    let c = match sanitize_byte(b) {
+                        '.' if b != b'.' => placeholder,
+                        c => c,
+                    };
+                    cell(w, i, Some(if redacted(i) { '*' } else { c }))?;
+                }
+            }
+            TextEncoding::Cp037 | TextEncoding::Cp500 => {
+                let table = match self {
+                    TextEncoding::Cp037 => &ebcdic::CP037,
+                    _ => &ebcdic::CP500,
+                };
+                for (i, &b) in chunk.iter().enumerate() {
+                    let c = match ebcdic::sanitize_latin1(table[b as usize]) {
+                        '.' if table[b as usize] != b'.' => placeholder,
+                        c => c,
+                    };
+                    cell(w, i, Some(if redacted(i) { '*' } else { c }))?;
+                }
+            }
+            TextEncoding::Utf16(endian) => {
+                let unit = |i: usize| {
+                    let pair = [chunk[i], chunk[i + 1]];
+                    match endian {
+                        Endian::Little => u16::from_le_bytes(pair),
+                        Endian::Big => u16::from_be_bytes(pair),
+                    }
+                };
+                let mut i = 0;
+                if !position.is_multiple_of(2) && !chunk.is_empty() {
+                    cell(w, 0, Some(if redacted(0) { '*' } else { placeholder }))?;
+                    i = 1;
+                }
+                while i < chunk.len() {
+                    if i + 1 == chunk.len() {
+                        cell(w, i, Some(if redacted(i) { '*' } else { placeholder }))?;
+                        break;
+                    }
+                    if redacted(i) || redacted(i + 1) {
+                        cell(w, i, Some('*'))?;
+                        cell(w, i + 1, Some('*'))?;
+                        i += 2;
+                        continue;
+                    }
+                    let mut units = [unit(i), 0];
+                    let mut num_units = 1;
+                    if (0xd800..0xdc00).contains(&units[0]) && i + 3 < chunk.len()
+                        && !redacted(i + 2) && !redacted(i + 3)
+                    {
+                        units[1] = unit(i + 2);
+                        num_units = 2;
+                    }
+                    let c = match char::decode_utf16(units[..num_units].iter().cloned()).next() {
+                        Some(Ok(c)) => c,
+                        _ => {
+                            cell(w, i, Some(placeholder))?;
+                            cell(w, i + 1, None)?;
+                            i += 2;
+                            continue;
+                        }
+                    };
+                    cell(w, i, Some(if c.is_control() { placeholder } else { c }))?;
+                    let len = 2 * c.len_utf16();
+                    for k in 1..len {
+                        cell(w, i + k, None)?;
+                    }
+                    i += len;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checksum that can be appended to each line, see `Config::checksum`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Checksum {
+    /// CRC-32 (IEEE 802.3) as used by zlib, shown as 8 hex digits.
+    Crc32,
+    /// Sum of the bytes modulo 256, shown as 2 hex digits.
+    Sum8,
+    /// Exclusive or of the bytes, shown as 2 hex digits.
+    Xor8,
+}
+
+impl Checksum {
+    /// Computes the checksum of the given bytes.
+    pub fn compute(self, bytes: &[u8]) -> u32 {
+        match self {
+            Checksum::Crc32 => {
+                let mut crc = !0u32;
+                for &b in bytes {
+                    crc ^= b as u32;
+                    for _ in 0..8 {
+                        let mask = (crc & 1).wrapping_neg();
+                        crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+                    }
+                }
+                !crc
+            }
+            Checksum::Sum8 => bytes.iter().fold(0u8, |a, &b| a.wrapping_add(b)) as u32,
+            Checksum::Xor8 => bytes.iter().fold(0u8, |a, &b| a ^ b) as u32,
+        }
+    }
+    /// Returns the number of hex digits used to display the checksum.
+    pub fn width(self) -> usize {
+        match self {
+            Checksum::Crc32 => 8,
+            Checksum::Sum8 | Checksum::Xor8 => 2,
+        }
+    }
+}
+
+/// Resolver set by `Config::symbols`.
+#[derive(Clone)]
+pub(crate) struct SymbolFn(pub(crate) Arc<dyn Fn(usize) -> Option<String> + Send + Sync>);
+
+impl fmt::Debug for SymbolFn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("SymbolFn(..)")
+    }
+}
+
+/// Callback set by `Config::progress`.
+#[derive(Clone)]
+pub(crate) struct ProgressFn(pub(crate) Arc<dyn Fn(usize, Option<usize>) + Send + Sync>);
+
+impl fmt::Debug for ProgressFn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ProgressFn(..)")
+    }
+}
+
+/// Configuration for customized hexdumps.
+///
+/// Start with `Config::new()`, which produces the same output as the free
+/// functions `hexdump` and `hexdump_iter`, and adjust it using the builder
+/// methods.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+/// hexdump::Config::new().title("packet #42").hexdump(b"abc");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Config {
+    bytes_per_line: Option<usize>,
+    pub(crate) byte_format: ByteFormat,
+    pub(crate) uppercase: bool,
+    pub(crate) plain_bytes_per_line: usize,
+    pub(crate) text_encoding: TextEncoding,
+    pub(crate) placeholder: char,
+    pub(crate) group_text: bool,
+    pub(crate) title: Option<String>,
+    pub(crate) max_lines: Option<usize>,
+    pub(crate) tail_lines: usize,
+    pub(crate) collapse_runs: Option<usize>,
+    pub(crate) normalize_offsets: bool,
+    pub(crate) base_offset: usize,
+    pub(crate) align_base_offset: bool,
+    pub(crate) anchor: Option<usize>,
+    pub(crate) entropy: bool,
+    pub(crate) heatmap: bool,
+    pub(crate) checksum: Option<Checksum>,
+    pub(crate) inspect: Vec<usize>,
+    pub(crate) annotations: Vec<(ops::Range<usize>, String)>,
+    pub(crate) layout: Option<Layout>,
+    pub(crate) style: Option<StyleFn>,
+    pub(crate) symbols: Option<SymbolFn>,
+    pub(crate) progress: Option<(usize, ProgressFn)>,
+    pub(crate) highlights: Vec<(Highlight, Style)>,
+    pub(crate) color: ColorChoice,
+    pub(crate) theme: Theme,
+    pub(crate) columns: Columns,
+    pub(crate) delimiters: (String, String),
+    pub(crate) segment_separator: String,
+    pub(crate) frame: bool,
+    pub(crate) ruler: bool,
+    pub(crate) ruler_every: Option<usize>,
+    pub(crate) summary_size: bool,
+    pub(crate) statistics: bool,
+    pub(crate) overview: bool,
+    pub(crate) redactions: Vec<ops::Range<usize>>,
+    pub(crate) line_terminator: LineTerminator,
+    pub(crate) sink: Sink,
+    trailing_newline: bool,
+    pub(crate) line_numbers: bool,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            bytes_per_line: None,
+            byte_format: ByteFormat::Hex,
+            uppercase: false,
+            plain_bytes_per_line: PLAIN_CHUNK_LENGTH,
+            text_encoding: TextEncoding::Ascii,
+            placeholder: '.',
+            group_text: false,
+            title: None,
+            max_lines: None,
+            tail_lines: 0,
+            collapse_runs: None,
+            normalize_offsets: false,
+            base_offset: 0,
+            align_base_offset: false,
+            anchor: None,
+            entropy: false,
+            heatmap: false,
+            checksum: None,
+            inspect: Vec::new(),
+            annotations: Vec::new(),
+            layout: None,
+            style: None,
+            symbols: None,
+            progress: None,
+            highlights: Vec::new(),
+            color: ColorChoice::Auto,
+            theme: Theme::default(),
+            columns: Columns::DataText,
+            delimiters: ("|".to_string(), "|".to_string()),
+            segment_separator: " ".to_string(),
+            frame: false,
+            ruler: false,
+            ruler_every: None,
+            summary_size: false,
+            statistics: false,
+            overview: false,
+            redactions: Vec::new(),
+            line_terminator: LineTerminator::Lf,
+            sink: Sink::Stdout,
+            trailing_newline: true,
+            line_numbers: false,
+        }
+    }
+}
+
+/// Returns whether `ColorChoice::Auto` enables colors for the given stream.
+fn auto_colors_enabled(sink: Sink) -> bool {
+    let terminal = sink.is_terminal();
+    style::colors_enabled(terminal) && (!terminal || console::enable_ansi(sink))
+}
+
+impl Config {
+    /// Creates the default configuration.
+    pub fn new() -> Config {
+        Config::default()
+    }
+    /// Creates the default configuration, adjusted by environment
+    /// variables.
+    ///
+    /// This lets the users of a program tune its dumps without the program
+    /// having to offer options for it. The variables are:
+    ///
+    /// - `HEXDUMP_COLS`: the number of bytes per line, a positive multiple
+    ///   of 4, see `bytes_per_line`.
+    /// - `HEXDUMP_GROUP`: whether the groups of 4 bytes are separated by a
+    ///   space, `1` by default. If `0`, the data column is contiguous.
+    /// - `HEXDUMP_UPPER`: whether hex digits are uppercase, see `uppercase`.
+    ///
+    /// Flags can be `1`, `true`, `yes` or `on` and `0`, `false`, `no` or
+    /// `off`. Unset variables, and those with an invalid value, are ignored.
+    pub fn from_env() -> Config {
+        Config::from_vars(|name| env::var(name).ok())
+    }
+    /// Like `from_env`, but looks up the variables using `var`.
+    pub(crate) fn from_vars<F: Fn(&str) -> Option<String>>(var: F) -> Config {
+        let flag = |name| match var(name)?.to_ascii_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Some(true),
+            "0" | "false" | "no" | "off" => Some(false),
+            _ => None,
+        };
+        let mut config = Config::new();
+        let cols = var("HEXDUMP_COLS").and_then(|value| value.parse::<usize>().ok());
+        if let Some(cols) = cols.filter(|&cols| cols != 0 && cols.is_multiple_of(4)) {
+            config = config.bytes_per_line(cols);
+        }
+        if flag("HEXDUMP_GROUP") == Some(false) {
+            config = config.segment_separator("");
+        }
+        if let Some(upper) = flag("HEXDUMP_UPPER") {
+            config = config.uppercase(upper);
+        }
+        config
+    }
+    /// Sets the number of bytes shown per line.
+    ///
+    /// Defaults to 16 for `ByteFormat::Hex`, 4 for `ByteFormat::Binary` and
+    /// 8 for the other formats.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes_per_line` is zero or not a multiple of 4.
+    pub fn bytes_per_line(mut self, bytes_per_line: usize) -> Config {
+        assert!(bytes_per_line != 0 && bytes_per_line.is_multiple_of(4),
+                "bytes per line must be a positive multiple of 4");
+        self.bytes_per_line = Some(bytes_per_line);
+        self
+    }
+    /// Sets the number of bytes per line of plain hex dumps, see
+    /// `hexdump_plain_iter`, 30 by default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes_per_line` is zero.
+    pub fn plain_bytes_per_line(mut self, bytes_per_line: usize) -> Config {
+        assert!(bytes_per_line != 0, "bytes per line must be positive");
+        self.plain_bytes_per_line = bytes_per_line;
+        self
+    }
+    pub(crate) fn chunk_length(&self) -> usize {
+        self.bytes_per_line.unwrap_or_else(|| self.byte_format.default_bytes_per_line())
+    }
+    /// Sets the numeral system used to show the bytes.
+    ///
+    /// With formats other than `ByteFormat::Hex`, the bytes of a group are
+    /// separated by a space and groups by two spaces.
+    pub fn byte_format(mut self, byte_format: ByteFormat) -> Config {
+        self.byte_format = byte_format;
+        self
+    }
+    /// Shows the bytes with uppercase hex digits.
+    ///
+    /// Only affects `ByteFormat::Hex`, the offsets stay lowercase.
+    pub fn uppercase(mut self, uppercase: bool) -> Config {
+        self.uppercase = uppercase;
+        self
+    }
+    /// Sets which of the data and the text column are shown, and in which
+    /// order.
+    ///
+    /// The widths of the lines, e.g. for `fit_to_width`, only take the shown
+    /// columns into account. Annotations and other markers point at the
+    /// text column if the data column is hidden. Lines without both columns
+    /// in the default order can't be parsed back, see `Line::data`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// extern crate hexdump;
+    /// use hexdump::Columns;
+    ///
+    /// let line = hexdump::Config::new().columns(Columns::Text)
+    ///     .hexdump_iter(b"abc").next().unwrap();
+    /// assert_eq!(line, "abc              00000000");
+    /// ```
+    pub fn columns(mut self, columns: Columns) -> Config {
+        self.columns = columns;
+        self
+    }
+    /// Sets the strings enclosing the data column, `|` by default.
+    ///
+    /// Either can be empty. The column math assumes each character takes up
+    /// one column.
+    pub fn delimiters<T, U>(mut self, open: T, close: U) -> Config
+        where T: Into<String>, U: Into<String>
+    {
+        self.delimiters = (open.into(), close.into());
+        self
+    }
+    /// Sets the string between groups of 4 bytes, a space by default.
+    ///
+    /// Can be empty. The column math assumes each character takes up one
+    /// column.
+    pub fn segment_separator<T: Into<String>>(mut self, separator: T) -> Config {
+        self.segment_separator = separator.into();
+        self
+    }
+    /// Frames the dump with Unicode box-drawing characters.
+    ///
+    /// The data, text and offset columns are separated by vertical lines and
+    /// topped by a header row labeling the position of each byte within the
+    /// line. The delimiters are replaced by the frame. A title stays above
+    /// the frame.
+    pub fn frame(mut self, frame: bool) -> Config {
+        self.frame = frame;
+        self
+    }
+    /// Emits a ruler line labeling the position of each byte within the
+    /// line, like `00 01 02 ... 0f`, above the data and the text column.
+    ///
+    /// In a frame, the header row serves as the ruler.
+    pub fn ruler(mut self, ruler: bool) -> Config {
+        self.ruler = ruler;
+        self
+    }
+    /// Repeats the ruler after every `lines` data lines, not counting
+    /// extra lines like annotations.
+    ///
+    /// Implies `ruler`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lines` is 0.
+    pub fn ruler_every(mut self, lines: usize) -> Config {
+        assert!(lines != 0, "ruler can't be repeated every 0 lines");
+        self.ruler = true;
+        self.ruler_every = Some(lines);
+        self
+    }
+    /// Sets the character encoding used for the text column.
+    pub fn text_encoding(mut self, text_encoding: TextEncoding) -> Config {
+        self.text_encoding = text_encoding;
+        self
+    }
+    /// Sets the character shown in the text column for bytes that don't
+    /// stand for a printable character, `.` by default.
+    ///
+    /// A character like `·` tells them apart from actual dots. It may take
+    /// up several bytes in UTF-8, but must take up a single column, which
+    /// rules out e.g. emoji and CJK characters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the character is a control character.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// extern crate hexdump;
+    ///
+    /// let line = hexdump::Config::new().placeholder('·').hexdump_iter(b"a.\x00").next().unwrap();
+    /// assert!(line.contains(" a.· "));
+    /// ```
+    pub fn placeholder(mut self, placeholder: char) -> Config {
+        assert!(!placeholder.is_control(), "placeholder must be printable");
+        self.placeholder = placeholder;
+        self
+    }
+    /// Splits the text column into groups of 4 characters separated by a
+    /// space, mirroring the segments of the data column.
+    ///
+    /// Lines with a grouped text column can't be parsed back, see
+    /// `Line::data`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// extern crate hexdump;
+    ///
+    /// let line = hexdump::Config::new().group_text(true)
+    ///     .hexdump_iter(b"abcdefghij").next().unwrap();
+    /// assert!(line.contains(" abcd efgh ij "));
+    /// ```
+    pub fn group_text(mut self, group_text: bool) -> Config {
+        self.group_text = group_text;
+        self
+    }
+    /// Picks the number of bytes per line so that lines fit into `width`
+    /// columns.
+    ///
+    /// Chooses the largest of 4, 8, 16, 32 or 64 bytes per line that fits,
+    /// falling back to 4 if none does. Takes the shown columns into
+    /// account, so call `columns` first.
+    pub fn fit_to_width(self, width: usize) -> Config {
+        let bytes_per_line = [64, 32, 16, 8].iter().cloned()
+            .find(|&n| line_width(&self, n) <= width)
+            .unwrap_or(4);
+        self.bytes_per_line(bytes_per_line)
+    }
+    /// Picks the number of bytes per line so that lines fit into the
+    /// terminal attached to stdout.
+    ///
+    /// Leaves the configuration unchanged if stdout is not a terminal.
+    ///
+    /// Requires the `terminal` feature.
+    #[cfg(feature = "terminal")]
+    pub fn fit_to_terminal(self) -> Config {
+        match terminal_size::terminal_size() {
+            Some((terminal_size::Width(width), _)) => self.fit_to_width(width as usize),
+            None => self,
+        }
+    }
+    /// Emits a title line before the dump.
+    ///
+    /// The line has the form `---- <title> (<n> bytes) ----` and is yielded
+    /// as the first item of the iterator.
+    pub fn title<T: Into<String>>(mut self, title: T) -> Config {
+        self.title = Some(title.into());
+        self
+    }
+    /// Limits the number of data lines.
+    ///
+    /// If the input needs more lines, the dump stops after `max_lines`
+    /// lines and emits a marker line of the form `... <n> bytes omitted ...`
+    /// before the summary line.
+    pub fn max_lines(mut self, max_lines: usize) -> Config {
+        self.max_lines = Some(max_lines);
+        self
+    }
+    /// Only shows the first `head` and the last `tail` data lines.
+    ///
+    /// If the input needs more lines, a single marker line of the form
+    /// `... <n> bytes omitted ...` separates the two parts. The offsets of
+    /// the tail lines refer to their actual position in the input.
+    pub fn head_tail(mut self, head: usize, tail: usize) -> Config {
+        self.max_lines = Some(head);
+        self.tail_lines = tail;
+        self
+    }
+    /// Collapses runs of a single repeated byte value of at least `min_len`
+    /// bytes, like zero padding or erased flash.
+    ///
+    /// Two or more consecutive lines consisting only of the same byte are
+    /// replaced by a single line of the form
+    /// `** 0x00 x 4096 bytes (0x1000..0x2000) **`, stating the byte, the
+    /// number of bytes and their range. Collapsed lines count as one line
+    /// for `max_lines`. Lines containing redacted bytes are never
+    /// collapsed.
+    pub fn collapse_runs(mut self, min_len: usize) -> Config {
+        self.collapse_runs = Some(min_len);
+        self
+    }
+    /// Appends the number of bytes in decimal and, from 1 KiB on, in binary
+    /// units to the summary line, e.g. `00000a3f  (2623 bytes, 2.6 KiB)`.
+    ///
+    /// Off by default, so the summary line only contains the offset.
+    pub fn summary_size(mut self, summary_size: bool) -> Config {
+        self.summary_size = summary_size;
+        self
+    }
+    /// Shows offsets relative to the first line of a range dump.
+    ///
+    /// With this option, dumping a range of a larger buffer yields the same
+    /// output regardless of where the range lies in the buffer, as long as
+    /// its alignment relative to the line boundaries stays the same. This
+    /// keeps snapshots of a region stable when unrelated data before it
+    /// changes.
+    pub fn normalize_offsets(mut self, normalize_offsets: bool) -> Config {
+        self.normalize_offsets = normalize_offsets;
+        self
+    }
+    /// Adds `base_offset` to all offsets shown, including the summary line.
+    ///
+    /// Useful for dumping a buffer that is part of a larger stream, see also
+    /// `Continuation`. Only the displayed offsets change, the lines still
+    /// start at the beginning of the buffer unless `align_base_offset` is
+    /// set, and annotations and redactions are still given relative to it.
+    ///
+    /// Dumping panics if the offset after the last byte doesn't fit into a
+    /// `usize`.
+    pub fn base_offset(mut self, base_offset: usize) -> Config {
+        self.base_offset = base_offset;
+        self
+    }
+    /// Returns the displayed offset after `len` bytes, see `base_offset`.
+    pub(crate) fn end_offset(&self, len: usize) -> usize {
+        match self.base_offset.checked_add(len) {
+            Some(end) => end,
+            None => panic!("base offset {:#x} plus {} bytes overflows usize",
+                           self.base_offset, len),
+        }
+    }
+    /// Aligns the lines to multiples of the line length in the displayed
+    /// offsets, like hex editors do.
+    ///
+    /// With an unaligned `base_offset` like `0x1f3`, the first line then
+    /// starts at `0x1f0` with its first 3 positions left blank, and all
+    /// following lines start at aligned offsets. This also makes the lines
+    /// of consecutive `Continuation` dumps line up. Doesn't apply to
+    /// `HexdumpStream`.
+    pub fn align_base_offset(mut self, align_base_offset: bool) -> Config {
+        self.align_base_offset = align_base_offset;
+        self
+    }
+    /// Shows offsets relative to `anchor`, with a sign.
+    ///
+    /// The anchor is given in terms of the offsets that would be shown
+    /// otherwise, the dump itself is unaffected. This is handy when a spec
+    /// gives field offsets relative to the start of a structure, e.g. a
+    /// header at 0x1a0 in a file.
+    pub fn relative_to(mut self, anchor: usize) -> Config {
+        self.anchor = Some(anchor);
+        self
+    }
+    /// Appends the Shannon entropy of each line's bytes to the line.
+    ///
+    /// The entropy is given in bits per byte with two decimal places. As a
+    /// line only contains a few bytes, it is bounded by the binary logarithm
+    /// of the number of bytes per line, i.e. 4 for 16 bytes per line. Values
+    /// close to that bound indicate compressed or encrypted data, values
+    /// close to 0 indicate padding.
+    pub fn entropy(mut self, entropy: bool) -> Config {
+        self.entropy = entropy;
+        self
+    }
+    /// Appends a statistics block after the summary line.
+    ///
+    /// The block reports the entropy of all dumped bytes in bits per byte,
+    /// the share of printable bytes (printable ASCII characters, tab, line
+    /// feed and carriage return), the share of zero bytes and the most
+    /// common bytes. This helps telling text, compressed or encrypted data
+    /// apart at a glance. Redacted bytes are left out.
+    pub fn statistics(mut self, statistics: bool) -> Config {
+        self.statistics = statistics;
+        self
+    }
+    /// Shows an overview of the dumped bytes after the title, see
+    /// `hexdump_overview_iter`.
+    pub fn overview(mut self, overview: bool) -> Config {
+        self.overview = overview;
+        self
+    }
+    /// Colors each byte of the data column according to its value.
+    ///
+    /// Uses 24-bit ANSI escape sequences: control and non-ASCII bytes are
+    /// shaded from dark gray for `0x00` to white for `0xff`, printable ASCII
+    /// characters are shaded in cyan. Redacted bytes aren't colored.
+    pub fn heatmap(mut self, heatmap: bool) -> Config {
+        self.heatmap = heatmap;
+        self
+    }
+    /// Styles each byte in both the data and the text column using the
+    /// given callback.
+    ///
+    /// The callback gets the position of the byte in the dumped buffer and
+    /// its value. Redacted bytes aren't styled.
+    pub fn style<F>(mut self, style: F) -> Config
+        where F: Fn(usize, u8) -> Style + Send + Sync + 'static
+    {
+        self.style = Some(StyleFn(Arc::new(style)));
+        self
+    }
+    /// Appends a symbol to each data line using the given resolver.
+    ///
+    /// The resolver gets the offset of the line as it is shown, i.e.
+    /// including `base_offset`, and returns the name of the symbol or
+    /// section at that offset, if any. The name is shown in angle brackets
+    /// after the offset.
+    pub fn symbols<F>(mut self, symbols: F) -> Config
+        where F: Fn(usize) -> Option<String> + Send + Sync + 'static
+    {
+        self.symbols = Some(SymbolFn(Arc::new(symbols)));
+        self
+    }
+    /// Reports the progress of long dumps by calling the given callback
+    /// after every `every` data lines and after the last one.
+    ///
+    /// The callback gets the number of bytes processed so far and the total
+    /// number of bytes, if known. Hexdump iterators know the total and
+    /// count lines skipped using `nth` as processed. Iterating backwards
+    /// reports the bytes processed from the end. Push-based streams, see
+    /// `hexdump_stream`, only know the total once they are finished. Lines
+    /// formatted out of order, e.g. using `Hexdump::line_at`, aren't
+    /// reported.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `every` is zero.
+    pub fn progress<F>(mut self, every: usize, progress: F) -> Config
+        where F: Fn(usize, Option<usize>) + Send + Sync + 'static
+    {
+        assert!(every != 0, "progress can't be reported every 0 lines");
+        self.progress = Some((every, ProgressFn(Arc::new(progress))));
+        self
+    }
+    /// Styles the bytes selected by a built-in rule.
+    ///
+    /// Can be called multiple times, the first matching rule decides the
+    /// style of a byte. Bytes styled by the callback passed to `style`
+    /// aren't affected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rule is `Highlight::Aligned(0)`.
+    pub fn highlight(mut self, rule: Highlight, style: Style) -> Config {
+        assert!(rule != Highlight::Aligned(0), "alignment must be positive");
+        self.highlights.push((rule, style));
+        self
+    }
+    /// Sets whether colors and styles are written, including the heatmap,
+    /// highlights and the colors of `dump_diff` and overviews.
+    ///
+    /// Defaults to `ColorChoice::Auto`, which keeps escape sequences out of
+    /// redirected output, files and strings. Use `ColorChoice::Always` for
+    /// colored lines or strings.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// extern crate hexdump;
+    /// use hexdump::ColorChoice;
+    /// use hexdump::Highlight;
+    ///
+    /// let config = hexdump::Config::new()
+    ///     .highlight(Highlight::NonAscii, Highlight::NonAscii.default_style());
+    /// assert!(!config.hexdump_string(b"\xff").contains('\x1b'));
+    /// let config = config.color(ColorChoice::Always);
+    /// assert!(config.hexdump_string(b"\xff").contains('\x1b'));
+    /// ```
+    pub fn color(mut self, color: ColorChoice) -> Config {
+        self.color = color;
+        self
+    }
+    /// Sets the palette of the built-in styles, i.e. the styles of
+    /// `highlight_themed` rules, `dump_diff` and colored overviews.
+    pub fn theme(mut self, theme: Theme) -> Config {
+        self.theme = theme;
+        self
+    }
+    /// Styles the bytes selected by a built-in rule with the style of the
+    /// rule in the theme set by `theme`.
+    ///
+    /// Set the theme first, see `highlight` for details.
+    pub fn highlight_themed(self, rule: Highlight) -> Config {
+        let style = self.theme.highlight(rule);
+        self.highlight(rule, style)
+    }
+    /// Returns whether colors and styles are written, with an unresolved
+    /// `ColorChoice::Auto` counting as not going to a terminal.
+    pub(crate) fn colors_enabled(&self) -> bool {
+        self.color == ColorChoice::Always
+    }
+    /// Resolves `ColorChoice::Auto` for output to the given standard
+    /// stream, for printing the lines of an iterator or a `dump_diff`
+    /// there.
+    ///
+    /// Also makes a Windows console interpret the escape sequences. The
+    /// printing methods like `hexdump` do this by themselves.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// extern crate hexdump;
+    /// use hexdump::Sink;
+    ///
+    /// let config = hexdump::Config::new().heatmap(true).resolve_color(Sink::Stdout);
+    /// for line in config.hexdump_search_iter(b"abcdef", b"cd", 0) {
+    ///     println!("{}", line);
+    /// }
+    /// ```
+    pub fn resolve_color(mut self, sink: Sink) -> Config {
+        match self.color {
+            ColorChoice::Always => {
+                if sink.is_terminal() {
+                    console::enable_ansi(sink);
+                }
+            }
+            ColorChoice::Auto => {
+                self.color = if auto_colors_enabled(sink) {
+                    ColorChoice::Always
+                } else {
+                    ColorChoice::Never
+                };
+            }
+            ColorChoice::Never => {}
+        }
+        self
+    }
+    /// Returns whether anything is styled, i.e. whether the color choice
+    /// matters for the lines.
+    pub(crate) fn has_styles(&self) -> bool {
+        self.style.is_some() || !self.highlights.is_empty() || self.heatmap
+    }
+    /// Returns the configuration with `ColorChoice::Auto` resolved for the
+    /// stream selected by `sink`.
+    ///
+    /// Without styles there's nothing to resolve, which skips checking for
+    /// a terminal and setting up a Windows console.
+    fn for_sink(&self) -> Config {
+        if self.has_styles() {
+            self.clone().resolve_color(self.sink)
+        } else {
+            self.clone()
+        }
+    }
+    /// Appends a checksum of each line's bytes to the line.
+    ///
+    /// Useful to catch transcription errors when comparing dumps visually.
+    pub fn checksum(mut self, checksum: Checksum) -> Config {
+        self.checksum = Some(checksum);
+        self
+    }
+    /// Interprets the bytes at the given offset like a hex editor's data
+    /// inspector.
+    ///
+    /// Beneath the line containing the offset, a line marking the byte is
+    /// emitted, followed by lines showing the bytes starting at the offset
+    /// as `u8`, `u16`, `u32`, `u64`, `f32` and `f64`, in both little and big
+    /// endian. Types that need more bytes than available are skipped. Can be
+    /// called multiple times to inspect several offsets.
+    pub fn inspect(mut self, offset: usize) -> Config {
+        self.inspect.push(offset);
+        self
+    }
+    /// Labels the given byte range.
+    ///
+    /// Beneath each line covering a part of the range, a line is emitted
+    /// that marks the hex representation of the covered bytes with `^` and
+    /// shows the label, followed by ` (cont.)` on all but the first of these
+    /// lines. Can be called multiple times, the annotations are shown in the
+    /// order they were added.
+    pub fn annotate<T: Into<String>>(mut self, range: ops::Range<usize>, label: T) -> Config {
+        self.annotations.push((range, label.into()));
+        self
+    }
+    /// Overlays a structure layout onto the dump.
+    ///
+    /// Each field of the layout is shown like an annotation (see
+    /// `annotate`), labeled with the field's name and its decoded value.
+    pub fn layout(mut self, layout: Layout) -> Config {
+        self.layout = Some(layout);
+        self
+    }
+    /// Masks the bytes in the given range.
+    ///
+    /// Their hex representation is shown as `xx` and their text
+    /// representation as `*`, while offsets stay unchanged. Entropy and
+    /// checksum columns of affected lines are masked as well, and layout
+    /// fields and inspected values don't reveal redacted bytes. Can be called
+    /// multiple times.
+    pub fn redact(mut self, range: ops::Range<usize>) -> Config {
+        self.redactions.push(range);
+        self
+    }
+    pub(crate) fn is_redacted(&self, offset: usize) -> bool {
+        self.redactions.iter().any(|r| r.contains(&offset))
+    }
+    /// Sets the line terminator used by `write`.
+    pub fn line_terminator(mut self, terminator: LineTerminator) -> Config {
+        self.line_terminator = terminator;
+        self
+    }
+    /// Prefixes the lines written by `write` and the functions built on it,
+    /// like `hexdump`, `hexdump_string` and `write_lines`, as well as those
+    /// of a `Dump`, with their number, starting at 1.
+    ///
+    /// All lines are numbered, so the numbers match what one would count
+    /// in a paste of the output. The numbers are right-aligned to the width
+    /// of the highest one if the number of lines is known up front, as for
+    /// `hexdump_iter`, and to at least four digits otherwise. The iterators
+    /// yield the lines unnumbered.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// extern crate hexdump;
+    ///
+    /// let dump = hexdump::Config::new().line_numbers(true).hexdump_string(b"abc");
+    /// assert_eq!(dump, "\
+    /// 1 |616263|                              abc              00000000
+    /// 2                                                        00000003
+    /// ");
+    /// ```
+    pub fn line_numbers(mut self, line_numbers: bool) -> Config {
+        self.line_numbers = line_numbers;
+        self
+    }
+    /// Returns the width of the line numbers for at most `max_lines`
+    /// lines, or `None` if lines aren't numbered.
+    pub(crate) fn line_number_width(&self, max_lines: Option<usize>) -> Option<usize> {
+        if !self.line_numbers {
+            return None;
+        }
+        Some(match max_lines {
+            Some(max_lines) => max_lines.to_string().len(),
+            None => 4,
+        })
+    }
+    /// Sets whether `write` terminates the last line, too.
+    ///
+    /// Defaults to `true`.
+    pub fn trailing_newline(mut self, trailing_newline: bool) -> Config {
+        self.trailing_newline = trailing_newline;
+        self
+    }
+    /// Sets the standard stream `hexdump` and `try_hexdump` print to,
+    /// stdout by default.
+    pub fn sink(mut self, sink: Sink) -> Config {
+        self.sink = sink;
+        self
+    }
+    /// Prints a hexdump of the given bytes to stdout, or to the stream
+    /// selected by `sink`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if writing fails, e.g. if stdout is a closed pipe. Use
+    /// `try_hexdump` to handle that case.
+    pub fn hexdump(&self, bytes: &[u8]) {
+        if let Err(e) = self.try_hexdump(bytes) {
+            let stream = match self.sink {
+                Sink::Stdout => "stdout",
+                Sink::Stderr => "stderr",
+            };
+            panic!("failed printing to {}: {}", stream, e);
+        }
+    }
+    /// Prints a hexdump of the given bytes to stdout, or to the stream
+    /// selected by `sink`, returning an error if that fails.
+    ///
+    /// A closed pipe, e.g. when piping into `head`, results in an error of
+    /// kind `io::ErrorKind::BrokenPipe`, which command line tools usually
+    /// want to treat as a normal exit.
+    pub fn try_hexdump(&self, bytes: &[u8]) -> io::Result<()> {
+        let config = self.for_sink();
+        config.print_lines(config.hexdump_iter(bytes))
+    }
+    /// Prints a hexdump of the given file to stdout, or to the stream
+    /// selected by `sink`.
+    ///
+    /// The file is memory-mapped instead of read, see `hexdump_file_iter`.
+    ///
+    /// Requires the `mmap` feature.
+    #[cfg(feature = "mmap")]
+    pub fn hexdump_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let config = self.for_sink();
+        config.print_lines(config.hexdump_file_iter(path)?)
+    }
+    /// Writes the given lines to the stream selected by `sink`.
+    fn print_lines<I: IntoIterator<Item=Line>>(&self, lines: I) -> io::Result<()> {
+        match self.sink {
+            Sink::Stdout => self.write_lines(io::stdout().lock(), lines),
+            Sink::Stderr => self.write_lines(io::stderr().lock(), lines),
+        }
+    }
+    /// Writes a hexdump of the given bytes to the given writer.
+    pub fn write<W: Write>(&self, writer: W, bytes: &[u8]) -> io::Result<()> {
+        self.write_lines(writer, self.hexdump_iter(bytes))
+    }
+    /// Writes a hexdump of the last `len` bytes of a seekable input, e.g. a
+    /// file, to the given writer.
+    ///
+    /// Only the last `len` bytes are read. The offsets shown are those in
+    /// the whole input, and the lines are aligned to them as with
+    /// `align_base_offset`.
+    ///
+    /// Returns an error of kind `io::ErrorKind::InvalidInput` if the offset
+    /// of the end of the input doesn't fit into a `usize`.
+    pub fn write_tail<W, R>(&self, writer: W, mut reader: R, len: usize) -> io::Result<()>
+        where W: Write, R: io::Read + io::Seek
+    {
+        let end = reader.seek(io::SeekFrom::End(0))?;
+        let start = end.saturating_sub(len as u64);
+        // Offsets beyond `usize` can't be shown, e.g. on 32-bit targets.
+        let end_offset = usize::try_from(end).ok()
+            .and_then(|end| self.base_offset.checked_add(end))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput,
+                                          "input offsets overflow usize"))?;
+        reader.seek(io::SeekFrom::Start(start))?;
+        let mut bytes = Vec::new();
+        reader.take(end - start).read_to_end(&mut bytes)?;
+        let config = self.clone()
+            .base_offset(end_offset - (end - start) as usize)
+            .align_base_offset(true);
+        config.write(writer, &bytes)
+    }
+    /// Writes the given lines to the given writer, terminated as configured.
+    ///
+    /// Useful for writing the output of the other iterators, e.g. the one
+    /// returned by `hexdump_search_iter`.
+    pub fn write_lines<W, I>(&self, mut writer: W, lines: I) -> io::Result<()>
+        where W: Write, I: IntoIterator<Item=Line>
+    {
+        let terminator = self.line_terminator.as_str();
+        let lines = lines.into_iter();
+        let number_width = self.line_number_width(lines.size_hint().1);
+        for (i, line) in lines.enumerate() {
+            if i != 0 {
+                writer.write_all(terminator.as_bytes())?;
+            }
+            if let Some(width) = number_width {
+                write!(writer, "{:>1$} ", i + 1, width)?;
+            }
+            writer.write_all(line.as_bytes())?;
+        }
+        if self.trailing_newline {
+            writer.write_all(terminator.as_bytes())?;
+        }
+        Ok(())
+    }
+    /// Returns the hexdump of the given bytes as a string.
+    ///
+    /// The lines are terminated as they would be by `write`.
+    pub fn hexdump_string(&self, bytes: &[u8]) -> String {
+        self.hexdump_range_string(bytes, 0..bytes.len())
+    }
+    /// Returns the lines of the hexdump of the given bytes, without line
+    /// terminators.
+    ///
+    /// The lines are those yielded by `hexdump_iter`, so `line_numbers`
+    /// doesn't apply.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// extern crate hexdump;
+    ///
+    /// let lines = hexdump::Config::new().hexdump_lines(&[0; 20]);
+    /// assert_eq!(lines.len(), 3);
+    /// assert!(lines[2].ends_with("00000014"));
+    /// ```
+    pub fn hexdump_lines(&self, bytes: &[u8]) -> Vec<String> {
+        self.hexdump_iter(bytes).map(|line| line.to_string()).collect()
+    }
+    /// Decodes a hex string and returns the hexdump of the bytes as a
+    /// string, e.g. for payloads copied from logs.
+    ///
+    /// See `decode_hex` for the accepted input.
+    pub fn hexdump_from_hex(&self, s: &str) -> Result<String, DecodeHexError> {
+        decode_hex(s).map(|bytes| self.hexdump_string(&bytes))
+    }
+    /// Returns the hexdump of the given bytes as a Markdown code block,
+    /// ready to be pasted into an issue or a document.
+    ///
+    /// The fence is longer than any run of backticks in the dump, so the
+    /// text column can't end the block early.
+    pub fn hexdump_markdown(&self, bytes: &[u8]) -> String {
+        markdown::code_block(&self.clone().line_terminator(LineTerminator::Lf)
+                                 .hexdump_string(bytes))
+    }
+    /// Returns the data lines of the hexdump of the given bytes as a
+    /// Markdown table with an offset, a hex and a text column.
+    ///
+    /// Each cell is a code span, with pipes escaped so they don't end the
+    /// cell. Only options affecting the contents of the columns apply, like
+    /// `byte_format`, `text_encoding` and `redact`.
+    pub fn hexdump_markdown_table(&self, bytes: &[u8]) -> String {
+        markdown::table(self, bytes)
+    }
+    /// Returns the hexdump of the given byte range as a string.
+    ///
+    /// See `hexdump_range_iter` for details.
+    pub fn hexdump_range_string(&self, bytes: &[u8], range: ops::Range<usize>) -> String {
+        let lines = self.hexdump_range_iter(bytes, range);
+        // Only an estimate, as frames, wide characters and extra columns
+        // make some lines longer.
+        let line_length = line_width(self, self.chunk_length())
+            + self.line_terminator.as_str().len();
+        let mut result = Vec::with_capacity(lines.len() * line_length);
+        self.write_lines(&mut result, lines)
+            .expect("writing to a Vec never fails");
+        String::from_utf8(result).expect("hexdump output is valid UTF-8")
+    }
+    /// Creates an iterator that only yields the lines containing `needle`,
+    /// plus `context` lines before and after them.
+    ///
+    /// Matches are marked beneath the lines containing them. To search for
+    /// a hex string, decode it with `decode_hex` first.
+    pub fn hexdump_search_iter<'a>(&self, bytes: &'a [u8], needle: &[u8], context: usize)
+        -> Search<'a>
+    {
+        Search::new(bytes, self, needle, context)
+    }
+    /// Creates an iterator over the lines of a sparse input given as
+    /// `(offset, bytes)` segments, e.g. the mapped regions of a core dump.
+    ///
+    /// Each segment is dumped with its true offsets, gaps between segments
+    /// are marked by a line like `... 0x2000 bytes unmapped ...`. The
+    /// segments must be sorted by offset and must not overlap.
+    pub fn hexdump_segments_iter<'a, I>(&self, segments: I) -> Segments<'a, I::IntoIter>
+        where I: IntoIterator<Item = (usize, &'a [u8])>
+    {
+        Segments::new(self, segments.into_iter())
+    }
+    /// Creates an iterator over the lines that differ between two inputs.
+    ///
+    /// The inputs are compared one line at a time, so memory use doesn't
+    /// depend on their size. Only options affecting individual lines apply,
+    /// titles, line limits and annotations are ignored.
+    pub fn hexdump_diff_iter<R1: io::Read, R2: io::Read>(&self, old: R1, new: R2)
+        -> Diff<R1, R2>
+    {
+        Diff::new(self, old, new)
+    }
+    /// Creates an iterator over the lines that differ between two files.
+    ///
+    /// See `hexdump_diff_iter` for details.
+    pub fn hexdump_diff_files<P: AsRef<Path>, Q: AsRef<Path>>(&self, old: P, new: Q)
+        -> io::Result<Diff<io::BufReader<fs::File>, io::BufReader<fs::File>>>
+    {
+        Diff::open(self, old, new)
+    }
+    /// Creates a wrapper that displays a hexdump of the given bytes.
+    ///
+    /// See `Dump` for the supported format parameters.
+    pub fn dump<'a>(&self, bytes: &'a [u8]) -> Dump<'a> {
+        Dump::new(bytes, self.clone())
+    }
+    /// Creates a wrapper that displays the hexdumps of two buffers side by
+    /// side, highlighting their differences.
+    ///
+    /// See `DumpDiff` for details.
+    pub fn dump_diff<'a>(&self, old: &'a [u8], new: &'a [u8]) -> DumpDiff<'a> {
+        DumpDiff::new(old, new, self.clone())
+    }
+    /// Creates a push-based hexdump for data arriving in pieces.
+    pub fn hexdump_stream(&self) -> HexdumpStream {
+        HexdumpStream::with_config(self)
+    }
+    /// Creates an iterator over the lines of a hexdump of the bytes of a
+    /// fallible source, e.g. `io::Read::bytes`, without collecting them
+    /// first.
+    ///
+    /// By default, the iterator yields the first error after the lines of
+    /// the bytes read before it and ends. Call `on_error` on it to mark
+    /// errors with a line instead, see `TryHexdump` for details.
+    pub fn try_hexdump_iter<I, E>(&self, bytes: I) -> TryHexdump<I::IntoIter, E>
+        where I: IntoIterator<Item=Result<u8, E>>
+    {
+        TryHexdump::new(self, bytes.into_iter())
+    }
+    /// Creates an iterator dumping the buffers of a blocking source as they
+    /// arrive, e.g. from a `std::sync::mpsc::Receiver<Vec<u8>>`, until it
+    /// is exhausted.
+    ///
+    /// The offsets continue from buffer to buffer, see `Live` for details.
+    pub fn hexdump_live_iter<I>(&self, buffers: I) -> Live<I::IntoIter>
+        where I: IntoIterator, I::Item: AsRef<[u8]>
+    {
+        Live::new(self, buffers.into_iter())
+    }
+    /// Wraps `inner` in a writer that also writes a hexdump of everything
+    /// written to `sink`.
+    pub fn hexdump_writer<W: Write, S: Write>(&self, inner: W, sink: S) -> HexdumpWriter<W, S> {
+        HexdumpWriter::new(self, inner, sink)
+    }
+    /// Wraps `inner` in a reader that also writes a hexdump of everything
+    /// read to `sink`.
+    pub fn hexdump_reader<R: io::Read, S: Write>(&self, inner: R, sink: S) -> HexdumpReader<R, S> {
+        HexdumpReader::new(self, inner, sink)
+    }
+    /// Creates a protocol trace tagging dumps with a sequence number and a
+    /// direction.
+    pub fn trace(&self) -> Trace {
+        Trace::new(self)
+    }
+    /// Creates a state object for dumping consecutive buffers of a stream
+    /// with continuing offsets.
+    pub fn continuation(&self) -> Continuation {
+        Continuation::new(self)
+    }
+    /// Formats a data line into a caller-provided buffer, returning the
+    /// number of bytes written.
+    ///
+    /// `offset` is the position of `chunk` in the dumped data, it determines
+    /// the offset shown and which bytes are redacted. The written bytes are
+    /// valid UTF-8 and contain no line terminator. Reusing the buffer avoids
+    /// creating a `Line` per line when dumping large amounts of data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk` is longer than a line or if the line doesn't fit
+    /// into `buf`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// extern crate hexdump;
+    ///
+    /// let mut buf = [0; 128];
+    /// let len = hexdump::Config::new().write_line_into(0x10, b"abc", &mut buf);
+    /// assert_eq!(&buf[..len], &b"|616263|                              abc              00000010"[..]);
+    /// ```
+    pub fn write_line_into(&self, offset: usize, chunk: &[u8], buf: &mut [u8]) -> usize {
+        assert!(chunk.len() <= self.chunk_length(),
+                "chunk of {} bytes exceeds the line length of {} bytes",
+                chunk.len(), self.chunk_length());
+        let mut writer = SliceWriter { buf, len: 0 };
+        write_line(&mut writer, self, offset, chunk).expect("buffer too small for the line");
+        writer.len
+    }
+    /// Creates an iterator over the bytes of each data line, together with
+    /// their offset as shown in the dump.
+    ///
+    /// Frontends doing their own rendering can use this to stay consistent
+    /// with the text output. The offset shown in the summary line is
+    /// available from `Chunks::end`.
+    pub fn hexdump_chunks<'a>(&self, bytes: &'a [u8]) -> Chunks<'a> {
+        Chunks::new(bytes, self)
+    }
+    /// Creates an iterator over the lines of an overview of the given
+    /// bytes, a grid with a character per cell of bytes showing their
+    /// class.
+    ///
+    /// Gives an idea of the structure of large inputs before looking at
+    /// parts of them in detail. Of the other options, only `base_offset`
+    /// and `redact` apply. See `Overview` for details.
+    pub fn hexdump_overview_iter<'a>(&self, bytes: &'a [u8]) -> Overview<'a> {
+        Overview::new(bytes, self, 0)
+    }
+    /// Creates an iterator over the rows of a CSV table of the data lines,
+    /// for loading dumps into spreadsheets or data frames.
+    ///
+    /// Each row holds the offset, the bytes and the text of a line. Only
+    /// options affecting the contents of these columns apply, like
+    /// `byte_format`, `text_encoding` and `redact`. See `Csv` for the
+    /// delimiter and quoting.
+    pub fn hexdump_csv_iter<'a>(&self, bytes: &'a [u8]) -> Csv<'a> {
+        Csv::new(bytes, self)
+    }
+    /// Creates an iterator over the strings of at least `min_len`
+    /// printable ASCII characters in the given bytes, like the `strings`
+    /// tool, instead of dumping all bytes.
+    ///
+    /// Yields a line like `00000010: "text"` per string, see `Strings`.
+    /// Redacted bytes end a string, and the offsets are shown as in data
+    /// lines, honoring e.g. `base_offset` and `relative_to`. Call `utf16` on
+    /// the iterator to also find UTF-16 strings.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min_len` is 0.
+    pub fn hexdump_strings_iter<'a>(&self, bytes: &'a [u8], min_len: usize) -> Strings<'a> {
+        Strings::new(bytes, self, min_len)
+    }
+    /// Creates an iterator over the lines of the dumps of consecutive
+    /// frames, e.g. packets or ring buffer entries.
+    ///
+    /// Each frame is dumped on its own, preceded by a header line with its
+    /// number, its length and its timestamp, if any. See `Frames` for
+    /// details.
+    pub fn hexdump_frames_iter<'a, I>(&self, frames: I) -> Frames<'a, I::IntoIter>
+        where I: IntoIterator, I::Item: IntoFrame<'a>
+    {
+        Frames::new(self, frames.into_iter())
+    }
+    /// Creates an iterator over the lines of a plain hex dump, the format
+    /// of `xxd -p`.
+    ///
+    /// Each line consists of the hex digits of `plain_bytes_per_line`
+    /// bytes, without offsets, separators or text. Of the other options,
+    /// only `uppercase` applies. `decode_hex` parses the output.
+    pub fn hexdump_plain_iter<'a>(&self, bytes: &'a [u8]) -> PlainHex<'a> {
+        PlainHex::new(bytes, self)
+    }
+    /// Creates an iterator over the lines of a packet in the format of
+    /// Wireshark's "Import from Hex Dump" and `text2pcap`.
+    ///
+    /// Of the other options, only `bytes_per_line` and `uppercase` apply.
+    /// See `Wireshark` for details.
+    pub fn hexdump_wireshark_iter<'a>(&self, bytes: &'a [u8]) -> Wireshark<'a> {
+        Wireshark::new(bytes, self)
+    }
+    /// Creates a watch over a region that is captured repeatedly, showing
+    /// only the lines that changed between captures.
+    ///
+    /// Each changed line is followed by a line marking its changed bytes
+    /// with `^`. Titles, summaries and the other options that only apply to
+    /// a complete dump aren't shown.
+    pub fn hexdump_watch(&self) -> Watch {
+        Watch::new(self)
+    }
+    /// Creates a hexdump iterator that yields the individual lines.
+    pub fn hexdump_iter<'a>(&self, bytes: &'a [u8]) -> Hexdump<'a> {
+        Hexdump::new(bytes, self.clone())
+    }
+    /// Creates a hexdump iterator over the lines of the given file.
+    ///
+    /// The file is memory-mapped and its pages are only loaded once the
+    /// lines covering them are formatted, so files of any size can be
+    /// dumped, and `Hexdump::line_at` gives cheap random access to their
+    /// lines. The iterator owns the mapping.
+    ///
+    /// The file must not be modified while it is mapped, doing so is
+    /// undefined behavior.
+    ///
+    /// Requires the `mmap` feature.
+    #[cfg(feature = "mmap")]
+    pub fn hexdump_file_iter<P: AsRef<Path>>(&self, path: P) -> io::Result<Hexdump<'static>> {
+        let bytes = mmap::map(path)?;
+        let len = bytes.len();
+        Ok(Hexdump::from_input(bytes, self.clone(), 0..len))
+    }
+    /// Creates a hexdump iterator over the lines of the given file covering
+    /// the given byte range.
+    ///
+    /// See `hexdump_file_iter` and `hexdump_range_iter` for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds for the file.
+    ///
+    /// Requires the `mmap` feature.
+    #[cfg(feature = "mmap")]
+    pub fn hexdump_file_range_iter<P: AsRef<Path>>(&self, path: P, range: ops::Range<usize>)
+        -> io::Result<Hexdump<'static>>
+    {
+        Ok(Hexdump::from_input(mmap::map(path)?, self.clone(), range))
+    }
+    /// Creates an iterator over the lines of a hexdump of the given memory
+    /// range of another process, read from `/proc/<pid>/mem`.
+    ///
+    /// The offsets shown are the virtual addresses of the bytes, and
+    /// unreadable pages are marked by a line each run, see `ProcessMemory`.
+    /// Reading the memory of another process usually requires the
+    /// `CAP_SYS_PTRACE` capability or being its parent, see `ptrace(2)`.
+    ///
+    /// Returns an error if the memory of the process can't be opened, e.g.
+    /// if it doesn't exist.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range ends before it starts.
+    ///
+    /// Requires the `process` feature and Linux.
+    #[cfg(all(feature = "process", target_os = "linux"))]
+    pub fn hexdump_process_iter(&self, pid: u32, range: ops::Range<usize>)
+        -> io::Result<ProcessMemory>
+    {
+        ProcessMemory::new(self, pid, range)
+    }
+    /// Creates a hexdump iterator that owns the given bytes.
+    ///
+    /// Unlike `hexdump_iter`, the iterator doesn't borrow anything, so it
+    /// can be returned from the function creating the bytes or moved to
+    /// another thread. See also `Hexdump::into_owned`.
+    pub fn hexdump_owned_iter(&self, bytes: Vec<u8>) -> Hexdump<'static> {
+        let len = bytes.len();
+        Hexdump::from_input(Input::Owned(bytes.into()), self.clone(), 0..len)
+    }
+    /// Creates a hexdump iterator over the UTF-8 encoding of the given
+    /// string that marks the bytes of each multi-byte character.
+    ///
+    /// Each such character is annotated like by `annotate`, with the
+    /// character and its code point as the label. The bytes between the
+    /// marked ranges are ASCII characters, so every byte not marked and
+    /// every first byte of a marked range is a character boundary.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// extern crate hexdump;
+    ///
+    /// let lines: Vec<_> = hexdump::Config::new().hexdump_str_iter("a€").collect();
+    /// assert_eq!(lines[1], "   ^^^^^^ '€' U+20AC");
+    /// ```
+    pub fn hexdump_str_iter<'a>(&self, s: &'a str) -> Hexdump<'a> {
+        let config = s.char_indices()
+            .filter(|&(_, c)| c.len_utf8() > 1)
+            .fold(self.clone(), |config, (i, c)| {
+                config.annotate(i..i + c.len_utf8(),
+                                format!("'{}' U+{:04X}", c.escape_debug(), c as u32))
+            });
+        config.hexdump_iter(s.as_bytes())
+    }
+    /// Creates a hexdump iterator that only yields the lines covering the
+    /// last `len` bytes, or all bytes if there are fewer.
+    ///
+    /// The lines before are neither formatted nor looked at. See
+    /// `hexdump_range_iter` for details.
+    pub fn hexdump_tail_iter<'a>(&self, bytes: &'a [u8], len: usize) -> Hexdump<'a> {
+        self.hexdump_range_iter(bytes, bytes.len().saturating_sub(len)..bytes.len())
+    }
+    /// Creates a hexdump iterator that only yields the lines covering the
+    /// given byte range.
+    ///
+    /// The offsets refer to the position in `bytes`, lines stay aligned to
+    /// the same boundaries as in a full dump, and the positions of partially
+    /// covered lines that lie outside of the range are left blank. The
+    /// summary line shows the end of the range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds for `bytes`.
+    pub fn hexdump_range_iter<'a>(&self, bytes: &'a [u8], range: ops::Range<usize>)
+        -> Hexdump<'a>
+    {
+        Hexdump::with_range(bytes, self.clone(), range)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ByteFormat;
+    use super::ColorChoice;
+    use super::Columns;
+    use super::Config;
+    use super::TextEncoding;
+    use display::dump_diff;
+    use highlight::Highlight;
+    use imp::hexdump_str_iter;
+    use imp::line_width;
+    use layout::Endian;
+    use style::Style;
+    use style::Theme;
+
+    #[test]
+    fn test_fit_to_width() {
+        assert_eq!(Config::new().fit_to_width(80).chunk_length(), 16);
+        assert_eq!(Config::new().fit_to_width(120).chunk_length(), 32);
+        assert_eq!(Config::new().fit_to_width(50).chunk_length(), 8);
+        assert_eq!(Config::new().fit_to_width(0).chunk_length(), 4);
+    }
+
+    #[test]
+    fn test_binary() {
+        let config = Config::new().byte_format(ByteFormat::Binary).annotate(1..2, "flags");
+        let lines: Vec<_> = config.hexdump_iter(b"\x01\x80ab\xff").map(|l| l.to_string()).collect();
+        assert_eq!(lines, [
+            "|00000001 10000000 01100001 01100010| ..ab 00000000",
+            "          ^^^^^^^^ flags",
+            "|11111111|                            .    00000004",
+            "                                           00000005",
+        ]);
+        assert_eq!(Config::new().byte_format(ByteFormat::Binary).bytes_per_line(8)
+                   .hexdump_iter(b"").next().unwrap().len(), 92);
+    }
+
+    #[test]
+    fn test_octal() {
+        let config = Config::new().byte_format(ByteFormat::Octal);
+        let lines: Vec<_> = config.hexdump_iter(b"ustar\x0000\xff").map(|l| l.to_string()).collect();
+        assert_eq!(lines, [
+            "|165 163 164 141  162 000 060 060| ustar.00 00000000",
+            "|377|                              .        00000008",
+            "                                            00000009",
+        ]);
+    }
+
+    #[test]
+    fn test_decimal() {
+        let bytes = b"\x00\x07\x80\xff\x7f\x01\x02\x03\x0a";
+        let config = Config::new().byte_format(ByteFormat::Decimal);
+        let lines: Vec<_> = config.hexdump_iter(bytes).map(|l| l.to_string()).collect();
+        assert_eq!(lines, [
+            "|  0   7 128 255  127   1   2   3| ........ 00000000",
+            "| 10|                              .        00000008",
+            "                                            00000009",
+        ]);
+        let config = Config::new().byte_format(ByteFormat::SignedDecimal);
+        let lines: Vec<_> = config.hexdump_iter(bytes).map(|l| l.to_string()).collect();
+        assert_eq!(lines, [
+            "|   0    7 -128   -1   127    1    2    3| ........ 00000000",
+            "|  10|                                     .        00000008",
+            "                                                    00000009",
+        ]);
+    }
+
+    #[test]
+    fn test_utf16() {
+        let bytes = b"H\0i\0\0\xd8\0\xdc\x1b\0\x00\xd8!\0\xe4\0z";
+        let config = Config::new().text_encoding(TextEncoding::Utf16(Endian::Little));
+        let lines: Vec<_> = config.hexdump_iter(bytes).map(|l| l.to_string()).collect();
+        assert_eq!(lines, [
+            "|48006900 00d800dc 1b0000d8 2100e400| H i \u{10000}   . . ! \u{e4}  00000000",
+            "|7a|                                  .                00000010",
+            "                                                       00000011",
+        ]);
+        let config = Config::new().text_encoding(TextEncoding::Utf16(Endian::Big))
+            .redact(2..3);
+        let lines: Vec<_> = config.hexdump_range_iter(b"\0a\0b\0c", 1..6)
+            .map(|l| l.to_string()).collect();
+        assert_eq!(lines[0], "  |61xx62 0063|                        .**c            00000000");
+    }
+
+    #[test]
+    fn test_heatmap() {
+        let config = Config::new().heatmap(true).redact(2..3).color(ColorChoice::Always);
+        let line = config.hexdump_iter(b"\x00a\xff").next().unwrap();
+        assert_eq!(line.to_string(), format!("|\x1b[38;2;64;64;64m00\x1b[0m\
+                                              \x1b[38;2;0;136;136m61\x1b[0mxx|{:30}.a*{:14}00000000",
+                                             "", ""));
+    }
+
+    #[test]
+    fn test_ruler() {
+        let config = Config::new().bytes_per_line(4).ruler_every(2).annotate(4..5, "x");
+        let lines: Vec<_> = config.hexdump_iter(b"0123456789").map(|l| l.to_string()).collect();
+        assert_eq!(lines, [
+            " 00010203  0123",
+            "|30313233| 0123 00000000",
+            "|34353637| 4567 00000004",
+            " ^^ x",
+            " 00010203  0123",
+            "|3839|     89   00000008",
+            "                0000000a",
+        ]);
+        let config = Config::new().byte_format(ByteFormat::Octal).bytes_per_line(8).ruler(true);
+        assert_eq!(config.hexdump_iter(b"").next().unwrap(),
+                   "   0   1   2   3    4   5   6   7  01234567");
+    }
+
+    #[test]
+    fn test_progress() {
+        use std::sync::Arc;
+        use std::sync::Mutex;
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let config = Config::new().progress(2, move |done, total| {
+            sink.lock().unwrap().push((done, total));
+        });
+        let bytes = [0; 100];
+        assert_eq!(config.hexdump_iter(&bytes).count(), 8);
+        assert!(reports.lock().unwrap().is_empty());
+        config.hexdump_iter(&bytes).for_each(drop);
+        assert_eq!(*reports.lock().unwrap(),
+                   [(32, Some(100)), (64, Some(100)), (96, Some(100)), (100, Some(100))]);
+        reports.lock().unwrap().clear();
+        config.hexdump_iter(&bytes).nth(3);
+        assert_eq!(*reports.lock().unwrap(), [(32, Some(100)), (64, Some(100))]);
+        reports.lock().unwrap().clear();
+        config.hexdump_iter(&bytes).rev().for_each(drop);
+        assert_eq!(*reports.lock().unwrap(),
+                   [(20, Some(100)), (52, Some(100)), (84, Some(100)), (100, Some(100))]);
+        reports.lock().unwrap().clear();
+
+        let mut stream = config.hexdump_stream();
+        stream.push(&bytes[..20]).for_each(drop);
+        stream.push(&bytes[20..50]).for_each(drop);
+        stream.finish().for_each(drop);
+        assert_eq!(*reports.lock().unwrap(), [(32, None), (50, Some(50))]);
+    }
+
+    #[test]
+    fn test_color_choice() {
+        let config = Config::new()
+            .heatmap(true)
+            .highlight(Highlight::NonAscii, Style::new().bold());
+        assert_eq!(config.clone().color(ColorChoice::Never).hexdump_string(b"a\xff"),
+                   Config::new().hexdump_string(b"a\xff"));
+        assert!(config.clone().color(ColorChoice::Always).hexdump_string(b"a\xff")
+            .contains('\x1b'));
+        // Writers and strings never count as terminals.
+        let mut written = Vec::new();
+        config.clone().color(ColorChoice::Auto).write(&mut written, b"a\xff").unwrap();
+        assert!(!written.contains(&0x1b));
+        assert_eq!(config.hexdump_string(b"a\xff"), Config::new().hexdump_string(b"a\xff"));
+        let theme = Theme { non_ascii: Style::new().underline(), ..Theme::default() };
+        let line = Config::new().theme(theme).highlight_themed(Highlight::NonAscii)
+            .color(ColorChoice::Always).hexdump_iter(b"a\xff").next().unwrap();
+        assert!(line.starts_with("|61\x1b[4mff\x1b[0m|"));
+        let diff = Config::new().color(ColorChoice::Never).dump_diff(b"ab", b"ac").to_string();
+        assert_eq!(diff, dump_diff(b"ab", b"ac").colored(false).to_string());
+    }
+
+    #[test]
+    fn test_columns() {
+        let bytes = b"abcdefghijklmnopqrs";
+        let lines = |columns| -> Vec<_> {
+            Config::new().columns(columns).ruler(true).annotate(1..3, "label")
+                .hexdump_iter(bytes).map(|l| l.to_string()).collect()
+        };
+        assert_eq!(lines(Columns::TextData), [
+            "0123456789abcdef  00010203 04050607 08090a0b 0c0d0e0f",
+            "abcdefghijklmnop |61626364 65666768 696a6b6c 6d6e6f70| 00000000",
+            "                    ^^^^ label",
+            "qrs              |717273|                              00000010",
+            "                                                       00000013",
+        ]);
+        assert_eq!(lines(Columns::Data), [
+            " 00010203 04050607 08090a0b 0c0d0e0f",
+            "|61626364 65666768 696a6b6c 6d6e6f70| 00000000",
+            "   ^^^^ label",
+            "|717273|                              00000010",
+            "                                      00000013",
+        ]);
+        assert_eq!(lines(Columns::Text), [
+            "0123456789abcdef",
+            "abcdefghijklmnop 00000000",
+            " ^^ label",
+            "qrs              00000010",
+            "                 00000013",
+        ]);
+        for &columns in &[Columns::DataText, Columns::TextData, Columns::Data, Columns::Text] {
+            let config = Config::new().columns(columns).frame(true);
+            for line in config.hexdump_iter(bytes) {
+                assert_eq!(line.chars().count(), line_width(&config, 16));
+            }
+            assert_eq!(config.clone().fit_to_width(line_width(&config, 32)).chunk_length(), 32);
+        }
+    }
+
+    #[test]
+    fn test_line_numbers() {
+        let bytes: Vec<u8> = (0..200).collect();
+        let config = Config::new().line_numbers(true).annotate(0..1, "first");
+        let numbered = config.hexdump_string(&bytes);
+        let lines: Vec<_> = numbered.lines().collect();
+        assert_eq!(lines.len(), 15);
+        assert_eq!(lines[0], format!(" 1 {}", config.hexdump_iter(&bytes).next().unwrap()));
+        assert_eq!(lines[1], " 2  ^^ first");
+        assert!(lines[14].starts_with("15  "));
+        assert_eq!(format!("{}", config.dump(&bytes)), numbered.trim_end());
+        // The number of lines isn't known up front.
+        let mut search = Vec::new();
+        config.write_lines(&mut search, config.hexdump_search_iter(&bytes, b"\x10", 0)).unwrap();
+        assert!(String::from_utf8(search).unwrap().starts_with("   1 |10111213"));
+    }
+
+    #[test]
+    fn test_placeholder() {
+        let config = Config::new().placeholder('·');
+        let lines: Vec<_> = config.hexdump_iter(b"a.\x00\xff").collect();
+        assert_eq!(lines[0], "|612e00ff|                            a.··             00000000");
+        let plain = Config::new().hexdump_iter(b"a.bc").next().unwrap();
+        assert_eq!(lines[0].chars().count(), plain.len());
+
+        let config = config.text_encoding(TextEncoding::Utf16(Endian::Little));
+        let line = config.hexdump_iter(b"a\x00\x01\x00").next().unwrap();
+        assert!(line.contains(" a ·"), "{}", line);
+    }
+
+    #[test]
+    fn test_group_text() {
+        let config = Config::new().group_text(true);
+        let lines: Vec<_> = config.hexdump_iter(&b"abcdefghijklmnopqr"[..]).collect();
+        assert_eq!(lines[0], "|61626364 65666768 696a6b6c 6d6e6f70| abcd efgh ijkl mnop 00000000");
+        assert_eq!(lines[1], "|7172|                                qr                  00000010");
+        assert_eq!(lines[2], &*format!("{:58}00000012", ""));
+
+        let ruler = config.clone().ruler(true).hexdump_iter(b"").next().unwrap();
+        assert_eq!(ruler, " 00010203 04050607 08090a0b 0c0d0e0f  0123 4567 89ab cdef");
+
+        // The text of a line starting in the middle keeps its place.
+        let line = config.hexdump_range_iter(&b"abcdefghijklmnopqr"[..], 3..8).next().unwrap();
+        assert_eq!(line, "      |64 65666768|                      d efgh           00000000");
+
+        // Wide characters may span a gap.
+        let config = config.text_encoding(TextEncoding::Utf16(Endian::Little));
+        let line = config.hexdump_iter(b"a\x00\x3d\xd8\x00\xdeb\x00").next().unwrap();
+        assert!(line.contains(" a \u{1f600}    b  "), "{}", line);
+        assert_eq!(line.chars().count(), lines[0].chars().count());
+    }
+
+    #[test]
+    fn test_from_vars() {
+        let config = |vars: &[(&str, &str)]| {
+            Config::from_vars(|name| vars.iter().find(|var| var.0 == name).map(|var| var.1.into()))
+        };
+        let bytes = b"\xab\xcd\xef\x01\x02\x03\x04\x05\x06";
+        let line = |config: Config| config.hexdump_iter(bytes).next().unwrap().to_string();
+        assert_eq!(line(config(&[])), line(Config::new()));
+        assert_eq!(line(config(&[("HEXDUMP_COLS", "8"), ("HEXDUMP_GROUP", "off"),
+                                 ("HEXDUMP_UPPER", "1")])),
+                   "|ABCDEF0102030405| ........ 00000000");
+        // Invalid values are ignored.
+        assert_eq!(line(config(&[("HEXDUMP_COLS", "6"), ("HEXDUMP_GROUP", "maybe"),
+                                 ("HEXDUMP_UPPER", "")])),
+                   line(Config::new()));
+    }
+
+    #[test]
+    fn test_hexdump_str_iter() {
+        let s = "a€\u{85}\u{1f600}b";
+        let lines: Vec<_> = hexdump_str_iter(s).map(|l| l.to_string()).collect();
+        assert_eq!(lines, [
+            "|61e282ac c285f09f 988062|            a.........b      00000000",
+            "   ^^^^^^ '€' U+20AC",
+            "          ^^^^ '\\u{85}' U+0085",
+            "              ^^^^^^^^^ '😀' U+1F600",
+            &format!("{:55}0000000b", ""),
+        ]);
+        let config = Config::new().bytes_per_line(4);
+        let lines: Vec<_> = config.hexdump_str_iter("ab€").map(|l| l.to_string()).collect();
+        assert_eq!(lines[1], "     ^^^^ '€' U+20AC");
+        assert_eq!(lines[3], " ^^ '€' U+20AC (cont.)");
+        assert_eq!(hexdump_str_iter("abc").count(), 2);
+    }
+
+    #[test]
+    fn test_hexdump_str_iter_scaling() {
+        // Looking up the annotations of a line must not depend on their
+        // total number, which took minutes for this many characters.
+        let s = "€".repeat(100_000);
+        let lines = hexdump_str_iter(&s);
+        // 18750 data lines, a line per character plus one per character
+        // split across two data lines, and the summary.
+        assert_eq!(lines.len(), 18750 + 100_000 + 12_500 + 1);
+        assert!(lines.clone().all(|line| !line.is_empty()));
+        let lines: Vec<_> = lines.rev().take(2).collect();
+        assert_eq!(lines[0], &*format!("{:55}000493e0", ""));
+        assert_eq!(lines[1], "                              ^^^^^^ '€' U+20AC");
+    }
+}
@@ -0,0 +1,44 @@
+use config::Sink;
+
+/// Makes the Windows console attached to the given stream interpret ANSI
+/// escape sequences, returning whether it does.
+///
+/// Consoles before Windows 10 don't support them and print them verbatim.
+/// Always returns `true` on other platforms, where terminals interpret them
+/// anyway.
+#[cfg(windows)]
+pub(crate) fn enable_ansi(sink: Sink) -> bool {
+    use std::os::raw::c_void;
+
+    const STD_OUTPUT_HANDLE: u32 = -11i32 as u32;
+    const STD_ERROR_HANDLE: u32 = -12i32 as u32;
+    const ENABLE_VIRTUAL_TERMINAL_PROCESSING: u32 = 0x0004;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetStdHandle(std_handle: u32) -> *mut c_void;
+        fn GetConsoleMode(console: *mut c_void, mode: *mut u32) -> i32;
+        fn SetConsoleMode(console: *mut c_void, mode: u32) -> i32;
+    }
+
+    let std_handle = match sink {
+        Sink::Stdout => STD_OUTPUT_HANDLE,
+        Sink::Stderr => STD_ERROR_HANDLE,
+    };
+    // SAFETY: The functions only read the handle and write to `mode`, and
+    // fail gracefully for invalid handles or handles that aren't consoles.
+    unsafe {
+        let handle = GetStdHandle(std_handle);
+        let mut mode = 0;
+        if GetConsoleMode(handle, &mut mode) == 0 {
+            return false;
+        }
+        mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0
+            || SetConsoleMode(handle, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0
+    }
+}
+
+#[cfg(not(windows))]
+pub(crate) fn enable_ansi(_sink: Sink) -> bool {
+    true
+}
@@ -0,0 +1,200 @@
+use config::Config;
+use imp::Buffer;
+use imp::Line;
+use imp::line_fields;
+use std::cmp;
+use std::fmt::Write;
+use std::iter;
+
+/// When the fields of a `Csv` row are enclosed in double quotes.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Quoting {
+    /// Only fields containing the delimiter, a double quote or a line
+    /// break, the default.
+    #[default]
+    Necessary,
+    /// All fields.
+    Always,
+    /// No fields, for consumers that split at the delimiter.
+    Never,
+}
+
+/// Return type of `hexdump_csv_iter`.
+///
+/// Yields a header row naming the `offset`, `hex` and `text` columns, and a
+/// row per data line. Quoted fields have their double quotes doubled, as
+/// described in RFC 4180.
+#[derive(Clone, Debug)]
+pub struct Csv<'a> {
+    bytes: &'a [u8],
+    config: Config,
+    delimiter: char,
+    quoting: Quoting,
+    header: bool,
+    front: usize,
+    back: usize,
+}
+
+/// Creates an iterator over the rows of a CSV table of the data lines of a
+/// hexdump.
+///
+/// See `Config::hexdump_csv_iter` for details.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+///
+/// let rows: Vec<_> = hexdump::hexdump_csv_iter(b"a,b").map(|r| r.to_string()).collect();
+/// assert_eq!(rows, ["offset,hex,text", "00000000,61 2c 62,\"a,b\""]);
+/// let rows: Vec<_> = hexdump::hexdump_csv_iter(b"a,b")
+///     .delimiter('\t')
+///     .header(false)
+///     .map(|r| r.to_string())
+///     .collect();
+/// assert_eq!(rows, ["00000000\t61 2c 62\ta,b"]);
+/// ```
+pub fn hexdump_csv_iter(bytes: &[u8]) -> Csv<'_> {
+    Config::new().hexdump_csv_iter(bytes)
+}
+
+impl<'a> Csv<'a> {
+    pub(crate) fn new(bytes: &'a [u8], config: &Config) -> Csv<'a> {
+        Csv {
+            bytes,
+            config: config.clone(),
+            delimiter: ',',
+            quoting: Quoting::Necessary,
+            header: true,
+            front: 0,
+            back: 1 + bytes.len().div_ceil(config.chunk_length()),
+        }
+    }
+    /// Sets the character separating the fields, `,` by default. Use `\t`
+    /// for TSV.
+    pub fn delimiter(mut self, delimiter: char) -> Csv<'a> {
+        self.delimiter = delimiter;
+        self
+    }
+    /// Sets when fields are quoted, `Quoting::Necessary` by default.
+    pub fn quoting(mut self, quoting: Quoting) -> Csv<'a> {
+        self.quoting = quoting;
+        self
+    }
+    /// Sets whether the header row is yielded, which is the default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if iteration has already started.
+    pub fn header(mut self, header: bool) -> Csv<'a> {
+        assert!(self.front == 0 && self.back == self.num_rows(),
+                "header must be set before iterating");
+        if header != self.header {
+            self.header = header;
+            self.back = self.num_rows();
+        }
+        self
+    }
+    fn num_rows(&self) -> usize {
+        let header = if self.header { 1 } else { 0 };
+        header + self.bytes.len().div_ceil(self.config.chunk_length())
+    }
+    fn write_field(&self, buf: &mut Buffer, field: &str) {
+        let quote = match self.quoting {
+            Quoting::Necessary => field.contains([self.delimiter, '"', '\r', '\n']),
+            Quoting::Always => true,
+            Quoting::Never => false,
+        };
+        if quote {
+            write!(buf, "\"{}\"", field.replace('"', "\"\"")).unwrap();
+        } else {
+            buf.write_str(field).unwrap();
+        }
+    }
+    fn row(&self, mut index: usize) -> Line {
+        let mut buf = Buffer::new();
+        let fields = if self.header && index == 0 {
+            ("offset".to_owned(), "hex".to_owned(), "text".to_owned())
+        } else {
+            if self.header {
+                index -= 1;
+            }
+            let chunk_length = self.config.chunk_length();
+            let start = index * chunk_length;
+            let end = cmp::min(start + chunk_length, self.bytes.len());
+            line_fields(&self.config, start, &self.bytes[start..end])
+        };
+        self.write_field(&mut buf, &fields.0);
+        buf.write_char(self.delimiter).unwrap();
+        self.write_field(&mut buf, &fields.1);
+        buf.write_char(self.delimiter).unwrap();
+        self.write_field(&mut buf, &fields.2);
+        Line::new(buf)
+    }
+}
+
+impl<'a> Iterator for Csv<'a> {
+    type Item = Line;
+    fn next(&mut self) -> Option<Line> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.row(self.front - 1))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
+}
+
+impl<'a> DoubleEndedIterator for Csv<'a> {
+    fn next_back(&mut self) -> Option<Line> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.row(self.back))
+    }
+}
+
+impl<'a> iter::FusedIterator for Csv<'a> {}
+
+impl<'a> ExactSizeIterator for Csv<'a> {
+    fn len(&self) -> usize {
+        self.back - self.front
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use config::ByteFormat;
+    use config::Config;
+
+    #[test]
+    fn test_csv() {
+        use csv::Quoting;
+        let bytes = b"say \"hi\"\tthere, you";
+        let config = Config::new().bytes_per_line(8).redact(0..1);
+        let rows: Vec<_> = config.hexdump_csv_iter(bytes).map(|r| r.to_string()).collect();
+        assert_eq!(rows, [
+            "offset,hex,text",
+            "00000000,xx 61 79 20 22 68 69 22,\"*ay \"\"hi\"\"\"",
+            "00000008,09 74 68 65 72 65 2c 20,\".there, \"",
+            "00000010,79 6f 75,you",
+        ]);
+        let rows: Vec<_> = config.hexdump_csv_iter(bytes)
+            .delimiter('\t')
+            .quoting(Quoting::Never)
+            .header(false)
+            .rev()
+            .map(|r| r.to_string())
+            .collect();
+        assert_eq!(rows[0], "00000010\t79 6f 75\tyou");
+        assert_eq!(rows.len(), 3);
+        let row = config.hexdump_csv_iter(b"").quoting(Quoting::Always).next().unwrap();
+        assert_eq!(row.to_string(), "\"offset\",\"hex\",\"text\"");
+        let decimal = Config::new().byte_format(ByteFormat::Decimal);
+        assert_eq!(decimal.hexdump_csv_iter(b"\x01\xff").nth(1).unwrap().to_string(),
+                   "00000000,1 255,..");
+    }
+}
@@ -0,0 +1,314 @@
+use config::Config;
+use imp::Buffer;
+use imp::Line;
+use imp::format_line;
+#[cfg(feature = "serde")] use serde::Deserialize;
+#[cfg(feature = "serde")] use serde::Serialize;
+use std::cmp;
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::io::Read;
+use std::path::Path;
+
+/// Return type of `hexdump_diff_iter`.
+///
+/// Reads both inputs one line's worth of bytes at a time and yields the
+/// lines that differ, the line of the old input prefixed with `-` followed
+/// by the line of the new input prefixed with `+`. If one input is longer
+/// than the other, its excess lines are yielded on their own. Groups of
+/// lines that are not adjacent are separated by a `--` line.
+///
+/// Yields an error and stops if reading from either input fails.
+pub struct Diff<R1, R2> {
+    config: Config,
+    old: R1,
+    new: R2,
+    old_buf: Vec<u8>,
+    new_buf: Vec<u8>,
+    offset: usize,
+    pending: VecDeque<Line>,
+    separator: bool,
+    adjacent: bool,
+    old_done: bool,
+    new_done: bool,
+}
+
+/// Creates an iterator over the lines that differ between `old` and `new`.
+///
+/// See `Config::hexdump_diff_iter` for details.
+pub fn hexdump_diff_iter<R1: Read, R2: Read>(old: R1, new: R2) -> Diff<R1, R2> {
+    Config::new().hexdump_diff_iter(old, new)
+}
+
+/// A run of bytes that differ between two inputs, see `diff_records`.
+///
+/// Bytes past the end of the shorter input are considered different, so
+/// `old` and `new` can have different lengths.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DiffRecord {
+    /// Offset of the first differing byte.
+    pub offset: usize,
+    /// The bytes of the old input.
+    pub old: Vec<u8>,
+    /// The bytes of the new input.
+    pub new: Vec<u8>,
+}
+
+/// Return type of `diff_records`.
+///
+/// Yields an error and stops if reading from either input fails.
+pub struct DiffRecords<R1, R2> {
+    old: R1,
+    new: R2,
+    old_buf: Vec<u8>,
+    new_buf: Vec<u8>,
+    old_len: usize,
+    new_len: usize,
+    old_done: bool,
+    new_done: bool,
+    offset: usize,
+    position: usize,
+    current: Option<DiffRecord>,
+}
+
+const BLOCK_LENGTH: usize = 8192;
+
+/// Creates an iterator over the runs of bytes that differ between `old`
+/// and `new`.
+///
+/// The inputs are read in blocks, so apart from the differing bytes
+/// themselves, memory use doesn't depend on their size.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+/// use hexdump::DiffRecord;
+///
+/// let records: Vec<_> = hexdump::diff_records(&b"abcdef"[..], &b"aBCdefg"[..])
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+/// assert_eq!(records, [
+///     DiffRecord { offset: 1, old: b"bc".to_vec(), new: b"BC".to_vec() },
+///     DiffRecord { offset: 6, old: vec![], new: b"g".to_vec() },
+/// ]);
+/// ```
+pub fn diff_records<R1: Read, R2: Read>(old: R1, new: R2) -> DiffRecords<R1, R2> {
+    DiffRecords {
+        old,
+        new,
+        old_buf: vec![0; BLOCK_LENGTH],
+        new_buf: vec![0; BLOCK_LENGTH],
+        old_len: 0,
+        new_len: 0,
+        old_done: false,
+        new_done: false,
+        offset: 0,
+        position: 0,
+        current: None,
+    }
+}
+
+impl<R1: Read, R2: Read> DiffRecords<R1, R2> {
+    fn refill(&mut self) -> io::Result<()> {
+        self.offset += cmp::max(self.old_len, self.new_len);
+        self.position = 0;
+        self.old_len = 0;
+        self.new_len = 0;
+        if !self.old_done {
+            self.old_len = read_full(&mut self.old, &mut self.old_buf)?;
+            self.old_done = self.old_len < self.old_buf.len();
+        }
+        if !self.new_done {
+            self.new_len = read_full(&mut self.new, &mut self.new_buf)?;
+            self.new_done = self.new_len < self.new_buf.len();
+        }
+        Ok(())
+    }
+}
+
+impl<R1: Read, R2: Read> Iterator for DiffRecords<R1, R2> {
+    type Item = io::Result<DiffRecord>;
+    fn next(&mut self) -> Option<io::Result<DiffRecord>> {
+        loop {
+            if self.position == cmp::max(self.old_len, self.new_len) {
+                if self.old_done && self.new_done {
+                    return self.current.take().map(Ok);
+                }
+                if let Err(e) = self.refill() {
+                    self.old_done = true;
+                    self.new_done = true;
+                    self.old_len = 0;
+                    self.new_len = 0;
+                    self.current = None;
+                    return Some(Err(e));
+                }
+                continue;
+            }
+            let old = self.old_buf[..self.old_len].get(self.position).cloned();
+            let new = self.new_buf[..self.new_len].get(self.position).cloned();
+            let offset = self.offset + self.position;
+            self.position += 1;
+            if old == new {
+                if let Some(record) = self.current.take() {
+                    return Some(Ok(record));
+                }
+                continue;
+            }
+            let record = self.current.get_or_insert_with(|| {
+                DiffRecord { offset, old: Vec::new(), new: Vec::new() }
+            });
+            record.old.extend(old);
+            record.new.extend(new);
+        }
+    }
+}
+
+/// Reads until `buf` is full or the end of the input is reached.
+pub(crate) fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+    while len < buf.len() {
+        match reader.read(&mut buf[len..]) {
+            Ok(0) => break,
+            Ok(n) => len += n,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(len)
+}
+
+impl<R1: Read, R2: Read> Diff<R1, R2> {
+    pub(crate) fn new(config: &Config, old: R1, new: R2) -> Diff<R1, R2> {
+        let chunk_length = config.chunk_length();
+        Diff {
+            config: config.clone(),
+            old,
+            new,
+            old_buf: vec![0; chunk_length],
+            new_buf: vec![0; chunk_length],
+            offset: 0,
+            pending: VecDeque::new(),
+            separator: false,
+            adjacent: false,
+            old_done: false,
+            new_done: false,
+        }
+    }
+}
+
+impl Diff<io::BufReader<fs::File>, io::BufReader<fs::File>> {
+    pub(crate) fn open<P: AsRef<Path>, Q: AsRef<Path>>(config: &Config, old: P, new: Q)
+        -> io::Result<Diff<io::BufReader<fs::File>, io::BufReader<fs::File>>>
+    {
+        let old = io::BufReader::new(fs::File::open(old)?);
+        let new = io::BufReader::new(fs::File::open(new)?);
+        Ok(Diff::new(config, old, new))
+    }
+}
+
+impl<R1: Read, R2: Read> Iterator for Diff<R1, R2> {
+    type Item = io::Result<Line>;
+    fn next(&mut self) -> Option<io::Result<Line>> {
+        if let Some(line) = self.pending.pop_front() {
+            return Some(Ok(line));
+        }
+        while !(self.old_done && self.new_done) {
+            let old_len = match self.old_done {
+                true => Ok(0),
+                false => read_full(&mut self.old, &mut self.old_buf),
+            };
+            let new_len = match self.new_done {
+                true => Ok(0),
+                false => read_full(&mut self.new, &mut self.new_buf),
+            };
+            let (old_len, new_len) = match (old_len, new_len) {
+                (Ok(o), Ok(n)) => (o, n),
+                (Err(e), _) | (_, Err(e)) => {
+                    self.old_done = true;
+                    self.new_done = true;
+                    return Some(Err(e));
+                }
+            };
+            self.old_done |= old_len < self.old_buf.len();
+            self.new_done |= new_len < self.new_buf.len();
+            let offset = self.offset;
+            self.offset += self.old_buf.len();
+            let old = &self.old_buf[..old_len];
+            let new = &self.new_buf[..new_len];
+            if old == new {
+                self.adjacent = false;
+                continue;
+            }
+            if self.separator && !self.adjacent {
+                let mut buf = Buffer::new();
+                buf.write_str("--").unwrap();
+                self.pending.push_back(Line::new(buf));
+            }
+            if !old.is_empty() {
+                self.pending.push_back(format_line(&self.config, offset, old).prefixed("-"));
+            }
+            if !new.is_empty() {
+                self.pending.push_back(format_line(&self.config, offset, new).prefixed("+"));
+            }
+            self.separator = true;
+            self.adjacent = true;
+            return self.pending.pop_front().map(Ok);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DiffRecord;
+    use super::diff_records;
+    use config::Config;
+
+    #[test]
+    fn test_diff() {
+        let old: Vec<u8> = (b'a'..=b'z').chain(b'a'..=b'z').collect();
+        let mut new = old.clone();
+        new[3] = b'D';
+        new[50] = b'Y';
+        new.extend_from_slice(b"!!");
+        let lines: Vec<_> = Config::new().hexdump_diff_iter(&old[..], &new[..])
+            .map(|l| l.unwrap().to_string()).collect();
+        assert_eq!(lines, [
+            "-|61626364 65666768 696a6b6c 6d6e6f70| abcdefghijklmnop 00000000",
+            "+|61626344 65666768 696a6b6c 6d6e6f70| abcDefghijklmnop 00000000",
+            "--",
+            "-|7778797a|                            wxyz             00000030",
+            "+|7778597a 2121|                       wxYz!!           00000030",
+        ]);
+        assert_eq!(Config::new().hexdump_diff_iter(&old[..], &old[..]).count(), 0);
+    }
+
+    #[test]
+    fn test_diff_records() {
+        let old = vec![0; 10000];
+        let mut new = old.clone();
+        for b in &mut new[8190..8195] {
+            *b = 1;
+        }
+        new[9000] = 2;
+        new.extend_from_slice(&[0, 0]);
+        let records: Vec<_> = diff_records(&old[..], &new[..]).map(|r| r.unwrap()).collect();
+        assert_eq!(records, [
+            DiffRecord { offset: 8190, old: vec![0; 5], new: vec![1; 5] },
+            DiffRecord { offset: 9000, old: vec![0], new: vec![2] },
+            DiffRecord { offset: 10000, old: vec![], new: vec![0, 0] },
+        ]);
+        assert_eq!(diff_records(&old[..], &old[..]).count(), 0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_diff_record_serde() {
+        fn assert_serde<T: ::serde::Serialize + for<'de> ::serde::Deserialize<'de>>() {}
+        assert_serde::<DiffRecord>();
+    }
+}
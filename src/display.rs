@@ -0,0 +1,290 @@
+use config::ColorChoice;
+use config::Config;
+use imp::Line;
+use imp::format_line;
+use imp::hexdump_summary;
+use std::cmp;
+use std::fmt;
+use style::Style;
+
+/// Wrapper displaying a hexdump, created by `dump` or `Config::dump`.
+///
+/// The lines are separated by the configured line terminator, without one
+/// after the last line. The standard format parameters adjust the dump:
+///
+/// * the width sets the number of bytes per line, rounded up to a positive
+///   multiple of 4,
+/// * the precision limits the dump to the first bytes,
+/// * the alternate flag `#` switches to uppercase hex digits.
+///
+/// `Dump` also implements `LowerHex` and `UpperHex`, so `{:x}` displays the
+/// same dump and `{:X}` one with uppercase hex digits.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+///
+/// let bytes: Vec<u8> = (0..100).collect();
+/// println!("{:#8.20}", hexdump::dump(&bytes));
+/// assert_eq!(format!("{:8.20}", hexdump::dump(&bytes)).lines().count(), 4);
+/// assert_eq!(format!("{:X}", hexdump::dump(&bytes)),
+///            format!("{:#}", hexdump::dump(&bytes)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Dump<'a> {
+    bytes: &'a [u8],
+    config: Config,
+}
+
+/// Creates a wrapper that displays a hexdump of the given bytes.
+///
+/// See `Dump` for the supported format parameters.
+pub fn dump(bytes: &[u8]) -> Dump<'_> {
+    Dump::new(bytes, Config::new())
+}
+
+impl<'a> Dump<'a> {
+    pub(crate) fn new(bytes: &'a [u8], config: Config) -> Dump<'a> {
+        Dump { bytes, config }
+    }
+}
+
+impl<'a> Dump<'a> {
+    fn write(&self, f: &mut fmt::Formatter, uppercase: bool) -> fmt::Result {
+        let mut config = self.config.clone();
+        if let Some(width) = f.width() {
+            config = config.bytes_per_line(cmp::max(width.div_ceil(4) * 4, 4));
+        }
+        if uppercase {
+            config = config.uppercase(true);
+        }
+        let end = f.precision().map_or(self.bytes.len(), |p| cmp::min(p, self.bytes.len()));
+        let terminator = config.line_terminator.as_str();
+        let lines = config.hexdump_range_iter(self.bytes, 0..end);
+        let number_width = config.line_number_width(Some(lines.len()));
+        for (i, line) in lines.enumerate() {
+            if i != 0 {
+                f.write_str(terminator)?;
+            }
+            if let Some(width) = number_width {
+                write!(f, "{:>1$} ", i + 1, width)?;
+            }
+            f.write_str(&line)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> fmt::Display for Dump<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(f, f.alternate())
+    }
+}
+
+impl<'a> fmt::LowerHex for Dump<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(f, false)
+    }
+}
+
+impl<'a> fmt::UpperHex for Dump<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(f, true)
+    }
+}
+
+/// Wrapper displaying the hexdumps of two buffers side by side, created by
+/// `dump_diff` or `Config::dump_diff`.
+///
+/// Rows whose lines differ are marked with `!` in front. If colors are
+/// enabled, see `Config::color` and `colored`, the differing bytes are
+/// highlighted, red in the old buffer on the left and green in the new one
+/// on the right. Bytes past the end of the shorter buffer count as
+/// different. The last row shows the summary lines of both buffers.
+///
+/// Only options affecting individual lines apply, titles, line limits,
+/// annotations and statistics are ignored. Styles set by `Config::style`
+/// are replaced by the highlighting.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+///
+/// let expected = b"\x01\x02\x03\x04";
+/// let actual = b"\x01\x02\xff\x04";
+/// if expected != actual {
+///     println!("frames differ:\n{}", hexdump::dump_diff(expected, actual));
+/// }
+/// let diff = hexdump::dump_diff(expected, actual).colored(false).to_string();
+/// assert!(diff.starts_with("! |01020304| "));
+/// ```
+#[derive(Clone, Debug)]
+pub struct DumpDiff<'a> {
+    old: &'a [u8],
+    new: &'a [u8],
+    config: Config,
+    colored: bool,
+}
+
+/// Creates a wrapper that displays the hexdumps of two buffers side by side,
+/// highlighting their differences.
+///
+/// See `DumpDiff` for details.
+pub fn dump_diff<'a>(old: &'a [u8], new: &'a [u8]) -> DumpDiff<'a> {
+    DumpDiff::new(old, new, Config::new())
+}
+
+impl<'a> DumpDiff<'a> {
+    pub(crate) fn new(old: &'a [u8], new: &'a [u8], config: Config) -> DumpDiff<'a> {
+        let colored = config.colors_enabled();
+        DumpDiff { old, new, config, colored }
+    }
+    /// Sets whether the differing bytes are highlighted using ANSI escape
+    /// sequences, see `Config::theme`.
+    ///
+    /// Defaults to `true` only for `ColorChoice::Always`, see
+    /// `Config::color`, as the wrapper doesn't know where it's displayed.
+    /// Rows that differ are marked either way.
+    pub fn colored(mut self, colored: bool) -> DumpDiff<'a> {
+        self.colored = colored;
+        self
+    }
+    /// Formats the line of `bytes` at the given offset, highlighting the
+    /// bytes differing from `other`, and returns it together with its width
+    /// without escape sequences.
+    fn side(&self, bytes: &[u8], other: &[u8], offset: usize, style: Style)
+        -> Option<(Line, usize)>
+    {
+        if offset >= bytes.len() {
+            return None;
+        }
+        let chunk = &bytes[offset..cmp::min(offset + self.config.chunk_length(), bytes.len())];
+        let plain = format_line(&self.config, offset, chunk);
+        let width = plain.chars().count();
+        let differing: Vec<bool> = chunk.iter().enumerate()
+            .map(|(i, b)| other.get(offset + i) != Some(b))
+            .collect();
+        if !self.colored || !differing.contains(&true) {
+            return Some((plain, width));
+        }
+        let config = self.config.clone().color(ColorChoice::Always).style(move |position, _| {
+            if differing[position - offset] {
+                style
+            } else {
+                Style::new()
+            }
+        });
+        Some((format_line(&config, offset, chunk), width))
+    }
+}
+
+/// Writes a row of a `DumpDiff`, padding the left line to `width`.
+fn write_row(f: &mut fmt::Formatter, differs: bool, left: Option<(Line, usize)>,
+             right: Option<(Line, usize)>, width: usize)
+    -> fmt::Result
+{
+    f.write_str(if differs { "! " } else { "  " })?;
+    let left_width = match left {
+        Some((ref line, line_width)) => {
+            f.write_str(line)?;
+            line_width
+        }
+        None => 0,
+    };
+    if let Some((line, _)) = right {
+        for _ in left_width..width + 2 {
+            f.write_str(" ")?;
+        }
+        f.write_str(&line)?;
+    }
+    Ok(())
+}
+
+impl<'a> fmt::Display for DumpDiff<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let chunk_length = self.config.chunk_length();
+        let len = cmp::max(self.old.len(), self.new.len());
+        let rows: Vec<_> = (0..len).step_by(chunk_length)
+            .map(|offset| {
+                let line = |bytes: &'a [u8]| {
+                    let start = cmp::min(offset, bytes.len());
+                    &bytes[start..cmp::min(offset + chunk_length, bytes.len())]
+                };
+                (line(self.old) != line(self.new),
+                 self.side(self.old, self.new, offset, self.config.theme.removed),
+                 self.side(self.new, self.old, offset, self.config.theme.added))
+            })
+            .collect();
+        let summary = |bytes: &[u8]| {
+            let line = hexdump_summary(&self.config, self.config.end_offset(bytes.len()),
+                                       bytes.len());
+            let width = line.chars().count();
+            (line, width)
+        };
+        let old_summary = summary(self.old);
+        let width = rows.iter()
+            .filter_map(|(_, left, _)| left.as_ref().map(|&(_, width)| width))
+            .fold(old_summary.1, cmp::max);
+        let terminator = self.config.line_terminator.as_str();
+        for (differs, left, right) in rows {
+            write_row(f, differs, left, right, width)?;
+            f.write_str(terminator)?;
+        }
+        write_row(f, self.old.len() != self.new.len(), Some(old_summary), Some(summary(self.new)),
+                  width)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::dump;
+    use super::dump_diff;
+    use config::ColorChoice;
+    use config::Config;
+    use config::LineTerminator;
+    use imp::hexdump_string;
+
+    #[test]
+    fn test_dump() {
+        let bytes = b"\xab\xcd\xef0123456789";
+        assert_eq!(format!("{}\n", dump(bytes)), hexdump_string(bytes));
+        assert_eq!(format!("{:#4.6}", dump(bytes)), format!("\
+            |ABCDEF30| ...0 00000000\n\
+            |3132|     12   00000004\n\
+            {:>24}", "00000006"));
+        let config = Config::new().line_terminator(LineTerminator::CrLf);
+        assert_eq!(format!("{:4}", config.dump(bytes)).matches("\r\n").count(), 4);
+        assert_eq!(format!("{:x}", dump(bytes)), format!("{}", dump(bytes)));
+        assert!(format!("{:X}", dump(bytes)).starts_with("|ABCDEF30 31323334"));
+        assert_eq!(format!("{:5}", dump(bytes)), format!("{:8}", dump(bytes)));
+        assert_eq!(format!("{:00}", dump(bytes)), format!("{:4}", dump(bytes)));
+    }
+
+    #[test]
+    fn test_dump_diff() {
+        let old: Vec<u8> = (b'a'..=b'z').collect();
+        let mut new = old.clone();
+        new[20] = b'U';
+        new.extend_from_slice(b"!");
+        let diff = dump_diff(&old, &new).colored(false).to_string();
+        let lines: Vec<_> = diff.lines().collect();
+        assert_eq!(lines, [
+            concat!("  |61626364 65666768 696a6b6c 6d6e6f70| abcdefghijklmnop 00000000",
+                    "  |61626364 65666768 696a6b6c 6d6e6f70| abcdefghijklmnop 00000000"),
+            concat!("! |71727374 75767778 797a|              qrstuvwxyz       00000010",
+                    "  |71727374 55767778 797a21|            qrstUvwxyz!      00000010"),
+            concat!("!                                                        0000001a",
+                    "                                                         0000001b"),
+        ]);
+        assert!(!dump_diff(&old, &new).to_string().contains('\x1b'));
+        let colored = Config::new().color(ColorChoice::Always).dump_diff(&old, &new).to_string();
+        assert!(colored.contains("\x1b[31;1m75\x1b[0m"));
+        assert!(colored.contains("\x1b[32;1m55\x1b[0m"));
+        assert!(colored.contains("\x1b[32;1m21\x1b[0m"));
+        assert!(!colored.lines().next().unwrap().contains('\x1b'));
+        assert_eq!(dump_diff(&old, &new).colored(true).to_string(), colored);
+        assert_eq!(dump_diff(&old, &old).colored(false).to_string().matches('!').count(), 0);
+    }
+}
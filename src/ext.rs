@@ -0,0 +1,41 @@
+use imp::Hexdump;
+use imp::hexdump;
+use imp::hexdump_iter;
+use imp::hexdump_string;
+
+/// Extension trait adding hexdump methods to byte buffers.
+///
+/// Implemented for everything that implements `AsRef<[u8]>`, e.g. `[u8]`,
+/// `Vec<u8>` and `str`. The methods behave like the free functions of the
+/// same name.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+/// use hexdump::HexdumpExt;
+///
+/// let frame = vec![0x02, b'h', b'i', 0x03];
+/// assert_eq!(frame.hexdump_iter().count(), 2);
+/// assert_eq!(frame.hexdump_string(), hexdump::hexdump_string(&frame));
+/// ```
+pub trait HexdumpExt {
+    /// Prints a hexdump to stdout, see `hexdump`.
+    fn hexdump(&self);
+    /// Creates an iterator over the lines of a hexdump, see `hexdump_iter`.
+    fn hexdump_iter(&self) -> Hexdump<'_>;
+    /// Returns a hexdump as a string, see `hexdump_string`.
+    fn hexdump_string(&self) -> String;
+}
+
+impl<T: AsRef<[u8]> + ?Sized> HexdumpExt for T {
+    fn hexdump(&self) {
+        hexdump(self.as_ref())
+    }
+    fn hexdump_iter(&self) -> Hexdump<'_> {
+        hexdump_iter(self.as_ref())
+    }
+    fn hexdump_string(&self) -> String {
+        hexdump_string(self.as_ref())
+    }
+}
@@ -0,0 +1,168 @@
+use config::Config;
+use imp::Buffer;
+use imp::Line;
+use std::fmt::Write;
+use std::iter;
+use std::vec;
+use stream::HexdumpStream;
+
+/// What a `TryHexdump` does when reading a byte fails.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum OnError {
+    /// Yield the error after the lines of the bytes read before it and
+    /// end, the default.
+    #[default]
+    Stop,
+    /// Emit a line like `<read error at offset 0x1f>` after the lines of
+    /// the bytes read before it and continue with the following bytes.
+    Mark,
+}
+
+/// Return type of `try_hexdump_iter`.
+///
+/// Dumps the bytes of a fallible source like `io::Bytes`, yielding a line
+/// as soon as its bytes are read. As with `HexdumpStream`, only options
+/// affecting individual lines and `Config::progress` apply. The summary
+/// line comes last, unless reading stopped at an error.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+/// use hexdump::OnError;
+/// use std::io;
+///
+/// let bytes = vec![Ok(b'a'), Err(io::Error::other("oops")), Ok(b'b')];
+/// let lines: Vec<_> = hexdump::try_hexdump_iter(bytes)
+///     .on_error(OnError::Mark)
+///     .map(|l| l.unwrap().to_string())
+///     .collect();
+/// assert_eq!(lines[1], "<read error at offset 0x1>");
+/// assert!(lines[2].ends_with("b                00000001"));
+/// ```
+#[derive(Debug)]
+pub struct TryHexdump<I, E> {
+    bytes: I,
+    config: Config,
+    on_error: OnError,
+    stream: Option<HexdumpStream>,
+    lines: vec::IntoIter<Line>,
+    error: Option<E>,
+}
+
+/// Creates an iterator over the lines of a hexdump of the bytes of a
+/// fallible source, e.g. `io::Read::bytes`.
+///
+/// See `Config::try_hexdump_iter` for details.
+pub fn try_hexdump_iter<I, E>(bytes: I) -> TryHexdump<I::IntoIter, E>
+    where I: IntoIterator<Item=Result<u8, E>>
+{
+    Config::new().try_hexdump_iter(bytes)
+}
+
+impl<I, E> TryHexdump<I, E> {
+    pub(crate) fn new(config: &Config, bytes: I) -> TryHexdump<I, E> {
+        TryHexdump {
+            bytes,
+            config: config.clone(),
+            on_error: OnError::Stop,
+            stream: Some(config.hexdump_stream()),
+            lines: Vec::new().into_iter(),
+            error: None,
+        }
+    }
+    /// Sets what happens when reading a byte fails, stopping by default.
+    pub fn on_error(mut self, on_error: OnError) -> TryHexdump<I, E> {
+        self.on_error = on_error;
+        self
+    }
+}
+
+impl<I: Iterator<Item=Result<u8, E>>, E> Iterator for TryHexdump<I, E> {
+    type Item = Result<Line, E>;
+    fn next(&mut self) -> Option<Result<Line, E>> {
+        loop {
+            if let Some(line) = self.lines.next() {
+                return Some(Ok(line));
+            }
+            if let Some(error) = self.error.take() {
+                return Some(Err(error));
+            }
+            let stream = self.stream.as_mut()?;
+            let chunk_length = self.config.chunk_length();
+            let mut chunk = Vec::with_capacity(chunk_length);
+            let mut error = None;
+            let mut done = false;
+            while chunk.len() < chunk_length {
+                match self.bytes.next() {
+                    Some(Ok(b)) => chunk.push(b),
+                    Some(Err(e)) => {
+                        error = Some(e);
+                        break;
+                    }
+                    None => {
+                        done = true;
+                        break;
+                    }
+                }
+            }
+            let mut lines: Vec<_> = stream.push(&chunk).collect();
+            if let Some(error) = error {
+                // Show the bytes read before the error right away.
+                lines.extend(stream.flush());
+                match self.on_error {
+                    OnError::Stop => {
+                        self.error = Some(error);
+                        self.stream = None;
+                    }
+                    OnError::Mark => {
+                        let mut buf = Buffer::new();
+                        write!(buf, "<read error at offset {:#x}>",
+                               self.config.end_offset(stream.len())).unwrap();
+                        lines.push(Line::new(buf));
+                    }
+                }
+            }
+            if done {
+                lines.extend(self.stream.take().unwrap().finish());
+            }
+            self.lines = lines.into_iter();
+        }
+    }
+}
+
+impl<I: Iterator<Item=Result<u8, E>>, E> iter::FusedIterator for TryHexdump<I, E> {}
+
+#[cfg(test)]
+mod test {
+    use super::OnError;
+    use super::try_hexdump_iter;
+    use config::Config;
+    use imp::format_line;
+    use imp::hexdump_iter;
+
+    #[test]
+    fn test_try_hexdump_iter() {
+        let bytes: Vec<u8> = (0..40).collect();
+        let lines: Vec<_> = try_hexdump_iter(bytes.iter().map(|&b| Ok::<u8, ()>(b)))
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(lines, hexdump_iter(&bytes).collect::<Vec<_>>());
+
+        let failing = || (0..20u8).map(|b| if b == 18 { Err(b) } else { Ok(b) });
+        let lines: Vec<_> = try_hexdump_iter(failing()).collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1], Ok(format_line(&Config::new(), 16, &[16, 17])));
+        assert_eq!(lines[2], Err(18));
+
+        let config = Config::new().base_offset(0x100);
+        let lines: Vec<_> = config.try_hexdump_iter(failing()).on_error(OnError::Mark)
+            .map(|l| l.unwrap().to_string())
+            .collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[2], "<read error at offset 0x112>");
+        assert!(lines[3].starts_with("|13|"));
+        assert!(lines[3].ends_with(" 00000112"));
+        assert!(lines[4].ends_with(" 00000113"));
+    }
+}
@@ -0,0 +1,124 @@
+//! C interface, enabled by the `ffi` feature.
+//!
+//! To build a shared library, run
+//!
+//! ```text
+//! cargo build --release --features ffi
+//! ```
+//!
+//! The corresponding declarations can be found in `include/hexdump.h`.
+
+use config::Config;
+use imp::CHUNK_LENGTH;
+use imp::format_line;
+use std::cmp;
+use std::os::raw::c_char;
+use std::os::raw::c_int;
+use std::os::raw::c_void;
+use std::ptr;
+use std::slice;
+
+/// Callback type of `hexdump_each`.
+pub type LineCallback = extern "C" fn(line: *const c_char, len: usize, user: *mut c_void);
+
+unsafe fn bytes<'a>(data: *const u8, len: usize) -> &'a [u8] {
+    if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(data, len)
+    }
+}
+
+/// Formats a single data line showing up to 16 bytes at `offset`.
+///
+/// Writes the NUL-terminated line to `out` if it fits into `out_len` bytes.
+/// Returns the length of the line, excluding the terminating NUL, like
+/// `snprintf`. Bytes beyond the first 16 are ignored.
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes and `out` to `out_len`
+/// writable bytes. Either may be null if the corresponding length is 0.
+#[no_mangle]
+pub unsafe extern "C" fn hexdump_line(data: *const u8, len: usize, offset: usize,
+                                      out: *mut c_char, out_len: usize) -> usize
+{
+    let data = bytes(data, cmp::min(len, CHUNK_LENGTH));
+    let line = format_line(&Config::new(), offset, data);
+    if line.len() < out_len {
+        ptr::copy_nonoverlapping(line.as_ptr(), out as *mut u8, line.len());
+        *out.add(line.len()) = 0;
+    }
+    line.len()
+}
+
+/// Calls `callback` for each line of the hexdump of the given bytes.
+///
+/// The line passed to the callback is NUL-terminated and only valid for the
+/// duration of the call. `user` is passed through unchanged. Returns 0 on
+/// success and -1 without doing anything if `callback` is null.
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes, it may be null if `len` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn hexdump_each(data: *const u8, len: usize,
+                                      callback: Option<LineCallback>, user: *mut c_void)
+    -> c_int
+{
+    let callback = match callback {
+        Some(c) => c,
+        None => return -1,
+    };
+    let mut buf = Vec::new();
+    for line in Config::new().hexdump_iter(bytes(data, len)) {
+        buf.clear();
+        buf.extend_from_slice(line.as_bytes());
+        buf.push(0);
+        callback(buf.as_ptr() as *const c_char, line.len(), user);
+    }
+    0
+}
+
+#[cfg(test)]
+mod test {
+    use super::hexdump_each;
+    use super::hexdump_line;
+    use std::ffi::CStr;
+    use std::os::raw::c_char;
+    use std::os::raw::c_void;
+    use std::ptr;
+
+    #[test]
+    fn test_line() {
+        let data = b"12345\0\r\n\t .abcdef";
+        let mut out = [0 as c_char; 64];
+        let len = unsafe { hexdump_line(data.as_ptr(), data.len(), 0x20, out.as_mut_ptr(), 64) };
+        let line = unsafe { CStr::from_ptr(out.as_ptr()) }.to_str().unwrap();
+        assert_eq!(line, "|31323334 35000d0a 09202e61 62636465| 12345.... .abcde 00000020");
+        assert_eq!(len, line.len());
+        let len = unsafe { hexdump_line(data.as_ptr(), 1, 0, out.as_mut_ptr(), 8) };
+        assert_eq!(len, 63);
+        assert_eq!(unsafe { hexdump_line(ptr::null(), 0, 0, ptr::null_mut(), 0) }, 63);
+    }
+
+    extern "C" fn collect(line: *const c_char, len: usize, user: *mut c_void) {
+        let lines = unsafe { &mut *(user as *mut Vec<String>) };
+        let line = unsafe { CStr::from_ptr(line) }.to_str().unwrap();
+        assert_eq!(line.len(), len);
+        lines.push(line.to_owned());
+    }
+
+    #[test]
+    fn test_each() {
+        let data = b"12345\0\r\n\t .abcdef";
+        let mut lines: Vec<String> = Vec::new();
+        unsafe {
+            let user = &mut lines as *mut _ as *mut c_void;
+            assert_eq!(hexdump_each(data.as_ptr(), data.len(), Some(collect), user), 0);
+            assert_eq!(hexdump_each(data.as_ptr(), data.len(), None, user), -1);
+        }
+        let expected: Vec<_> = ::hexdump_iter(data).map(|l| l.to_string()).collect();
+        assert_eq!(lines, expected);
+    }
+}
@@ -0,0 +1,131 @@
+use config::Column;
+use config::Config;
+use imp::Buffer;
+use imp::Line;
+use imp::column_width;
+use imp::line_width;
+use imp::offset_width;
+use imp::ruler_labels;
+use std::fmt::Write;
+use std::iter;
+
+/// Border in front of the data column.
+pub(crate) const LEFT: &str = "│ ";
+/// Border between two columns.
+pub(crate) const MIDDLE: &str = " │ ";
+/// Border after the offset column.
+pub(crate) const RIGHT: &str = " │";
+
+/// Returns the width of the offset column including the entropy and
+/// checksum columns.
+pub(crate) fn tail_width(config: &Config) -> usize {
+    let entropy = if config.entropy { 5 } else { 0 };
+    let checksum = config.checksum.map(|c| 1 + c.width()).unwrap_or(0);
+    offset_width(config) + entropy + checksum
+}
+
+/// Returns the total width of a framed line.
+fn width(config: &Config) -> usize {
+    line_width(config, config.chunk_length()) - offset_width(config) + tail_width(config)
+}
+
+fn border(config: &Config, left: &str, middle: &str, right: &str) -> Line {
+    let chunk_length = config.chunk_length();
+    let widths = config.columns.order().iter()
+        .map(|&column| column_width(config, column, chunk_length))
+        .chain(iter::once(tail_width(config)));
+    let mut buf = Buffer::new();
+    buf.write_str(left).unwrap();
+    for (i, width) in widths.enumerate() {
+        if i != 0 {
+            buf.write_str(middle).unwrap();
+        }
+        for _ in 0..width + 2 {
+            buf.write_str("─").unwrap();
+        }
+    }
+    buf.write_str(right).unwrap();
+    Line::new(buf)
+}
+
+pub(crate) fn top(config: &Config) -> Line {
+    border(config, "┌", "┬", "┐")
+}
+
+pub(crate) fn separator(config: &Config) -> Line {
+    border(config, "├", "┼", "┤")
+}
+
+pub(crate) fn bottom(config: &Config) -> Line {
+    border(config, "└", "┴", "┘")
+}
+
+/// Formats the header row labeling the position of each byte within the
+/// line.
+pub(crate) fn header(config: &Config) -> Line {
+    let (data, text) = ruler_labels(config);
+    row(config, &data, &text, "offset")
+}
+
+/// Formats a row from the contents of its columns, leaving out those of
+/// hidden columns.
+pub(crate) fn row(config: &Config, data: &str, text: &str, tail: &str) -> Line {
+    let chunk_length = config.chunk_length();
+    let mut buf = Buffer::new();
+    buf.write_str(LEFT).unwrap();
+    for &column in config.columns.order() {
+        let contents = match column {
+            Column::Data => data,
+            Column::Text => text,
+        };
+        write!(buf, "{:1$}", contents, column_width(config, column, chunk_length)).unwrap();
+        buf.write_str(MIDDLE).unwrap();
+    }
+    write!(buf, "{:1$}", tail, tail_width(config)).unwrap();
+    buf.write_str(RIGHT).unwrap();
+    Line::new(buf)
+}
+
+/// Encloses a line that doesn't follow the columns, like an annotation, in
+/// the outer border.
+///
+/// Returns the line unchanged if the output isn't framed.
+pub(crate) fn wrap(config: &Config, line: Line) -> Line {
+    if !config.frame {
+        return line;
+    }
+    let mut buf = Buffer::new();
+    // Lines that start with spaces are aligned with the data column.
+    let prefix = if line.starts_with(' ') { "│" } else { LEFT };
+    buf.write_str(prefix).unwrap();
+    buf.write_str(&line).unwrap();
+    let len = buf.chars().count();
+    for _ in len..width(config) - 1 {
+        buf.write_str(" ").unwrap();
+    }
+    buf.write_str("│").unwrap();
+    Line::new(buf)
+}
+
+#[cfg(test)]
+mod test {
+    use config::Config;
+    use imp::line_width;
+
+    #[test]
+    fn test_frame() {
+        let config = Config::new().bytes_per_line(4).frame(true).annotate(1..3, "x");
+        let lines: Vec<_> = config.hexdump_iter(b"abcdef").map(|l| l.to_string()).collect();
+        assert_eq!(lines, [
+            "┌──────────┬──────┬──────────┐",
+            "│ 00010203 │ 0123 │ offset   │",
+            "├──────────┼──────┼──────────┤",
+            "│ 61626364 │ abcd │ 00000000 │",
+            "│   ^^^^ x                   │",
+            "│ 6566     │ ef   │ 00000004 │",
+            "│          │      │ 00000006 │",
+            "└──────────┴──────┴──────────┘",
+        ]);
+        assert!(lines.iter().all(|l| l.chars().count() == line_width(&config, 4)));
+    }
+}
@@ -0,0 +1,123 @@
+use config::Config;
+use imp::Hexdump;
+use imp::Line;
+use std::time::Duration;
+
+/// A frame passed to `hexdump_frames_iter`, optionally with a timestamp.
+///
+/// Implemented for byte slices and for pairs of a timestamp, e.g. the time
+/// since the start of the capture, and a byte slice.
+pub trait IntoFrame<'a> {
+    /// Returns the timestamp, if any, and the bytes of the frame.
+    fn into_frame(self) -> (Option<Duration>, &'a [u8]);
+}
+
+impl<'a> IntoFrame<'a> for &'a [u8] {
+    fn into_frame(self) -> (Option<Duration>, &'a [u8]) {
+        (None, self)
+    }
+}
+
+impl<'a> IntoFrame<'a> for &'a Vec<u8> {
+    fn into_frame(self) -> (Option<Duration>, &'a [u8]) {
+        (None, self)
+    }
+}
+
+impl<'a> IntoFrame<'a> for (Duration, &'a [u8]) {
+    fn into_frame(self) -> (Option<Duration>, &'a [u8]) {
+        (Some(self.0), self.1)
+    }
+}
+
+/// Return type of `hexdump_frames_iter`.
+///
+/// Yields the dumps of the frames one after another, each preceded by a
+/// header line of the form `---- frame <n> (<len> bytes) ----`, or
+/// `---- frame <n> at <seconds>s (<len> bytes) ----` for frames with a
+/// timestamp. Frames are numbered from 1, and the offsets of each frame
+/// start at the base offset. The title set by `Config::title` is replaced
+/// by the header lines.
+pub struct Frames<'a, I> {
+    config: Config,
+    frames: I,
+    current: Option<Hexdump<'a>>,
+    number: usize,
+}
+
+/// Creates an iterator over the lines of the dumps of the given frames.
+///
+/// See `Config::hexdump_frames_iter` for details.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+/// use std::time::Duration;
+///
+/// let frames = [
+///     (Duration::from_micros(0), &b"ping"[..]),
+///     (Duration::from_micros(1500), &b"pong"[..]),
+/// ];
+/// let lines: Vec<_> = hexdump::hexdump_frames_iter(frames.iter().cloned()).collect();
+/// assert_eq!(lines[0].to_string(), "---- frame 1 at 0.000000s (4 bytes) ----");
+/// assert_eq!(lines[3].to_string(), "---- frame 2 at 0.001500s (4 bytes) ----");
+/// assert!(lines[4].ends_with(" 00000000"));
+/// ```
+pub fn hexdump_frames_iter<'a, I>(frames: I) -> Frames<'a, I::IntoIter>
+    where I: IntoIterator, I::Item: IntoFrame<'a>
+{
+    Config::new().hexdump_frames_iter(frames)
+}
+
+impl<'a, I> Frames<'a, I> {
+    pub(crate) fn new(config: &Config, frames: I) -> Frames<'a, I> {
+        Frames {
+            config: config.clone(),
+            frames,
+            current: None,
+            number: 0,
+        }
+    }
+}
+
+impl<'a, I> Iterator for Frames<'a, I> where I: Iterator, I::Item: IntoFrame<'a> {
+    type Item = Line;
+    fn next(&mut self) -> Option<Line> {
+        loop {
+            if let Some(line) = self.current.as_mut().and_then(|c| c.next()) {
+                return Some(line);
+            }
+            let (timestamp, bytes) = self.frames.next()?.into_frame();
+            self.number += 1;
+            let title = match timestamp {
+                Some(t) => format!("frame {} at {}.{:06}s", self.number, t.as_secs(),
+                                   t.subsec_micros()),
+                None => format!("frame {}", self.number),
+            };
+            self.current = Some(Hexdump::new(bytes, self.config.clone().title(title)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use config::Config;
+
+    #[test]
+    fn test_frames() {
+        let frames = vec![vec![1; 20], vec![], vec![2; 3]];
+        let lines: Vec<_> = Config::new().title("ignored").hexdump_frames_iter(&frames)
+            .map(|l| l.to_string())
+            .collect();
+        let mut expected = Vec::new();
+        for (i, frame) in frames.iter().enumerate() {
+            let config = Config::new().title(format!("frame {}", i + 1));
+            expected.extend(config.hexdump_iter(frame).map(|l| l.to_string()));
+        }
+        assert_eq!(lines, expected);
+        assert_eq!(lines[0], "---- frame 1 (20 bytes) ----");
+        assert_eq!(lines[4], "---- frame 2 (0 bytes) ----");
+        assert!(lines[7].ends_with(" 00000000"));
+    }
+}
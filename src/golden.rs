@@ -0,0 +1,98 @@
+use config::Config;
+use imp::CHUNK_LENGTH;
+use std::cmp;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Lines shown before and after the line with the first difference.
+const CONTEXT_LINES: usize = 2;
+
+/// Environment variable that makes `assert_bytes_matches_file!` write the
+/// bytes to the golden file instead of comparing them.
+const UPDATE_VAR: &str = "HEXDUMP_UPDATE_GOLDEN";
+
+/// Implementation of `assert_bytes_matches_file!`.
+#[doc(hidden)]
+pub fn assert_bytes_matches_file(bytes: &[u8], path: &Path) {
+    let update = env::var_os(UPDATE_VAR).is_some_and(|value| !value.is_empty() && value != "0");
+    if let Err(message) = check_file(bytes, path, update) {
+        panic!("{}", message);
+    }
+}
+
+/// Compares `bytes` with the contents of the file at `path`, or writes them
+/// to it if `update` is set.
+///
+/// Returns the message to panic with on mismatch.
+pub(crate) fn check_file(bytes: &[u8], path: &Path, update: bool) -> Result<(), String> {
+    if update {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("can't create `{}`: {}", parent.display(), e))?;
+        }
+        return fs::write(path, bytes)
+            .map_err(|e| format!("can't write golden file `{}`: {}", path.display(), e));
+    }
+    let expected = match fs::read(path) {
+        Ok(expected) => expected,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => {
+            return Err(format!("golden file `{}` doesn't exist, set {}=1 to create it",
+                               path.display(), UPDATE_VAR));
+        }
+        Err(e) => return Err(format!("can't read golden file `{}`: {}", path.display(), e)),
+    };
+    let first = match bytes.iter().zip(&expected).position(|(a, b)| a != b) {
+        Some(first) => first,
+        None if bytes.len() == expected.len() => return Ok(()),
+        None => cmp::min(bytes.len(), expected.len()),
+    };
+    // Only show the lines around the first difference, aligned as in a
+    // full dump.
+    let line = first / CHUNK_LENGTH;
+    let start = line.saturating_sub(CONTEXT_LINES) * CHUNK_LENGTH;
+    let end = (line + CONTEXT_LINES + 1) * CHUNK_LENGTH;
+    let diff = Config::new()
+        .base_offset(start)
+        .dump_diff(window(&expected, start, end), window(bytes, start, end));
+    Err(format!("bytes don't match golden file `{}` at offset {:#x} (expected {} bytes, \
+                 got {})\n{}\nset {}=1 to update the file",
+                path.display(), first, expected.len(), bytes.len(), diff, UPDATE_VAR))
+}
+
+fn window(bytes: &[u8], start: usize, end: usize) -> &[u8] {
+    &bytes[cmp::min(start, bytes.len())..cmp::min(end, bytes.len())]
+}
+
+#[cfg(test)]
+mod test {
+    use super::check_file;
+
+    #[test]
+    fn test_golden_file() {
+        use std::env;
+        use std::fs;
+        use std::process;
+        let dir = env::temp_dir().join(format!("hexdump-golden-{}", process::id()));
+        let path = dir.join("nested").join("golden.bin");
+        let bytes: Vec<u8> = (0..100).collect();
+        let message = check_file(&bytes, &path, false).unwrap_err();
+        assert!(message.contains("doesn't exist"), "{}", message);
+        check_file(&bytes, &path, true).unwrap();
+        check_file(&bytes, &path, false).unwrap();
+        assert_bytes_matches_file!(bytes, &path);
+
+        let mut changed = bytes.clone();
+        changed[0x50] = 0xff;
+        let message = check_file(&changed, &path, false).unwrap_err();
+        assert!(message.contains("at offset 0x50 (expected 100 bytes, got 100)"), "{}", message);
+        // Only the lines around the difference are shown.
+        assert!(message.contains("! |"), "{}", message);
+        assert!(message.contains(" 00000030"), "{}", message);
+        assert!(!message.contains(" 00000020"), "{}", message);
+        let message = check_file(&bytes[..98], &path, false).unwrap_err();
+        assert!(message.contains("at offset 0x62"), "{}", message);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
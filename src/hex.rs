@@ -0,0 +1,107 @@
+use config::Config;
+use std::error;
+use std::fmt;
+
+/// Error returned by `decode_hex`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DecodeHexError {
+    position: usize,
+}
+
+impl DecodeHexError {
+    /// Returns the byte position in the input at which decoding failed.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl fmt::Display for DecodeHexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid hex string at position {}", self.position)
+    }
+}
+
+impl error::Error for DecodeHexError {}
+
+fn hex_digit(b: u8) -> Option<u8> {
+    match b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'a'..=b'f' => Some(b - b'a' + 10),
+        b'A'..=b'F' => Some(b - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Decodes a hex string into bytes.
+///
+/// Whitespace separates groups of hex digits, each of which may be prefixed
+/// by `0x` or `0X` and must consist of an even number of digits. This
+/// includes the output of `hexdump_plain_iter` and `xxd -p`.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+/// assert_eq!(hexdump::decode_hex("0x1234 ab\ncd").unwrap(), b"\x12\x34\xab\xcd");
+/// assert!(hexdump::decode_hex("123").is_err());
+/// ```
+pub fn decode_hex(s: &str) -> Result<Vec<u8>, DecodeHexError> {
+    let input = s.as_bytes();
+    let mut result = Vec::with_capacity(input.len() / 2);
+    let mut i = 0;
+    while i < input.len() {
+        if input[i].is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+        let prefix = input.get(i..i + 2);
+        if prefix == Some(b"0x") || prefix == Some(b"0X") {
+            i += 2;
+        }
+        while i < input.len() && !input[i].is_ascii_whitespace() {
+            let high = hex_digit(input[i]).ok_or(DecodeHexError { position: i })?;
+            let low = input.get(i + 1).cloned().and_then(hex_digit)
+                .ok_or(DecodeHexError { position: i + 1 })?;
+            result.push(high << 4 | low);
+            i += 2;
+        }
+    }
+    Ok(result)
+}
+
+/// Decodes a hex string and returns the hexdump of the bytes as a string.
+///
+/// See `decode_hex` for the accepted input.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+/// assert_eq!(hexdump::hexdump_from_hex("0x6162").unwrap(), hexdump::hexdump_string(b"ab"));
+/// ```
+pub fn hexdump_from_hex(s: &str) -> Result<String, DecodeHexError> {
+    Config::new().hexdump_from_hex(s)
+}
+
+#[cfg(test)]
+mod test {
+    use super::decode_hex;
+    use super::hexdump_from_hex;
+    use config::Config;
+    use imp::hexdump_string;
+
+    #[test]
+    fn test_decode_hex() {
+        assert_eq!(decode_hex("").unwrap(), b"");
+        assert_eq!(decode_hex(" 0x01 0X02\t0304 ").unwrap(), b"\x01\x02\x03\x04");
+        assert_eq!(decode_hex("0x1").unwrap_err().position(), 3);
+        assert_eq!(decode_hex("zz").unwrap_err().position(), 0);
+        assert_eq!(decode_hex("0xx").unwrap_err().position(), 2);
+
+        assert_eq!(hexdump_from_hex("0x00ff 41\n").unwrap(), hexdump_string(b"\x00\xffA"));
+        assert_eq!(hexdump_from_hex("").unwrap(), hexdump_string(b""));
+        assert_eq!(hexdump_from_hex("0041 4").unwrap_err().position(), 6);
+        let config = Config::new().uppercase(true);
+        assert_eq!(config.hexdump_from_hex("ab").unwrap(), config.hexdump_string(b"\xab"));
+    }
+}
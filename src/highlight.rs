@@ -0,0 +1,110 @@
+use std::str;
+use style::Style;
+use style::Theme;
+
+/// Built-in rule selecting bytes to style, see `Config::highlight`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Highlight {
+    /// Runs of at least four zero bytes, typical for padding.
+    NulRuns,
+    /// Runs of at least four `0xff` bytes, typical for erased flash.
+    FfRuns,
+    /// Bytes outside the ASCII range.
+    NonAscii,
+    /// Bytes that aren't part of a valid UTF-8 sequence.
+    Utf8Errors,
+    /// Bytes whose offset is a multiple of the given number.
+    ///
+    /// # Panics
+    ///
+    /// `Config::highlight` panics if the number is zero.
+    Aligned(usize),
+}
+
+const MIN_RUN_LENGTH: usize = 4;
+
+impl Highlight {
+    /// Returns the style of this rule in the default `Theme`, which is
+    /// used by the command line interface.
+    pub fn default_style(self) -> Style {
+        Theme::default().highlight(self)
+    }
+    /// Returns whether the rule selects `bytes[index]`, which is shown at
+    /// `offset`.
+    ///
+    /// Only the bytes in `bytes` are taken into account for runs and UTF-8
+    /// sequences.
+    pub(crate) fn matches(self, bytes: &[u8], index: usize, offset: usize) -> bool {
+        match self {
+            Highlight::NulRuns => in_run(bytes, index, 0x00),
+            Highlight::FfRuns => in_run(bytes, index, 0xff),
+            Highlight::NonAscii => bytes[index] >= 0x80,
+            Highlight::Utf8Errors => is_utf8_error(bytes, index),
+            Highlight::Aligned(n) => offset.is_multiple_of(n),
+        }
+    }
+}
+
+fn in_run(bytes: &[u8], index: usize, byte: u8) -> bool {
+    if bytes[index] != byte {
+        return false;
+    }
+    let before = bytes[..index].iter().rev().take(MIN_RUN_LENGTH - 1)
+        .take_while(|&&b| b == byte).count();
+    let after = bytes[index + 1..].iter().take(MIN_RUN_LENGTH - 1)
+        .take_while(|&&b| b == byte).count();
+    before + 1 + after >= MIN_RUN_LENGTH
+}
+
+fn is_utf8_error(bytes: &[u8], index: usize) -> bool {
+    if bytes[index] < 0x80 {
+        return false;
+    }
+    // Look for the start of a sequence covering the byte.
+    for start in (index.saturating_sub(3)..=index).rev() {
+        let len = match bytes[start] {
+            0xc2..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf4 => 4,
+            _ => continue,
+        };
+        return !(start + len > index && start + len <= bytes.len()
+                 && str::from_utf8(&bytes[start..start + len]).is_ok());
+    }
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::Highlight;
+    use config::ColorChoice;
+    use config::Config;
+    use style::Color;
+    use style::Style;
+
+    #[test]
+    fn test_highlight() {
+        let red = Style::new().foreground(Color::Red);
+        let blue = Style::new().foreground(Color::Blue);
+        // Counts the highlighted bytes in the text column.
+        let count = |rule: Highlight, bytes: &[u8]| {
+            let config = Config::new().highlight(rule, red).color(ColorChoice::Always);
+            let line = config.hexdump_iter(bytes).next().unwrap();
+            line.rsplit('|').next().unwrap().matches("\x1b[31m").count()
+        };
+        assert_eq!(count(Highlight::NulRuns, b"\0\0\0a\0\0\0\0"), 4);
+        assert_eq!(count(Highlight::FfRuns, b"\xff\xff\xff\xff\xff\0\xff"), 5);
+        assert_eq!(count(Highlight::NonAscii, b"a\x80\xffb"), 2);
+        assert_eq!(count(Highlight::Utf8Errors, "a\u{e4}\u{20ac}".as_bytes()), 0);
+        assert_eq!(count(Highlight::Utf8Errors, b"\xe2\x82\xac\x80\xc3a\xf0\x9f\x98"), 5);
+        assert_eq!(count(Highlight::Aligned(4), &[0; 10]), 3);
+        // The first matching rule wins, the callback takes precedence.
+        let config = Config::new()
+            .style(|offset, _| if offset == 0 { Style::new().bold() } else { Style::new() })
+            .highlight(Highlight::NonAscii, red)
+            .highlight(Highlight::Aligned(2), blue)
+            .color(ColorChoice::Always);
+        let line = config.hexdump_iter(b"\x80\x80\x80a").next().unwrap();
+        assert!(line.starts_with("|\x1b[1m80\x1b[0m\x1b[31m80\x1b[0m\x1b[31m80\x1b[0m61|"));
+    }
+}
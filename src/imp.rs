@@ -1,58 +1,377 @@
-use arrayvec::ArrayString;
+use config::ByteFormat;
+use config::Column;
+use config::Columns;
+use config::Config;
+use config::Sink;
+use config::TextEncoding;
+use frame;
+use inspect;
+use overview::Overview;
+use stats::Statistics;
+use std::borrow;
+use std::cmp;
+use std::error;
 use std::fmt;
 use std::fmt::Write;
+use std::hash;
+use std::io;
 use std::iter;
+use std::mem;
 use std::ops;
-use std::slice;
+use std::ptr;
+use std::str;
+use std::sync;
+use style::Style;
 
 const SEGMENT_LENGTH: usize = 4;
 // CHUNK_LENGTH should be a multiple of SEGMENT_LENGTH
-const CHUNK_LENGTH: usize = 16;
+pub(crate) const CHUNK_LENGTH: usize = 16;
 
-const NUM_SEGMENTS_PER_CHUNK: usize = (CHUNK_LENGTH + SEGMENT_LENGTH - 1) / SEGMENT_LENGTH;
+/// Default inline capacity of a `Line` in bytes.
+pub(crate) const BUFFER_LENGTH: usize = 64;
 
-const BUFFER_LENGTH: usize = 64;
+/// Length marking a `Buffer` whose contents have spilled onto the heap.
+const SPILLED: u8 = u8::MAX;
 
-type BufferImpl = ArrayString<[u8; BUFFER_LENGTH]>;
+/// Line storage that stays inline for regular lines and only spills onto
+/// the heap for unusually long ones (e.g. titles).
+///
+/// Once spilled, `len` is `SPILLED` and `bytes` holds the pointer to the
+/// boxed `String`, which keeps the default `Line` as small as 64 inline
+/// bytes and a length.
+pub(crate) struct Buffer<const N: usize = BUFFER_LENGTH> {
+    len: u8,
+    bytes: [u8; N],
+}
+
+impl<const N: usize> Buffer<N> {
+    pub(crate) fn new() -> Buffer<N> {
+        assert!(mem::size_of::<*mut String>() <= N && N < SPILLED as usize,
+                "inline capacity {} out of range", N);
+        Buffer { len: 0, bytes: [0; N] }
+    }
+    fn heap_ptr(&self) -> Option<*mut String> {
+        if self.len != SPILLED {
+            return None;
+        }
+        // SAFETY: Spilled buffers store a pointer from `Box::into_raw` at
+        // the start of `bytes`, see `spill`.
+        Some(unsafe { ptr::read_unaligned(self.bytes.as_ptr() as *const *mut String) })
+    }
+    fn spill(&mut self, heap: String) {
+        debug_assert!(self.len != SPILLED);
+        let heap = Box::into_raw(Box::new(heap));
+        // SAFETY: `new` asserts that `bytes` can hold a pointer.
+        unsafe { ptr::write_unaligned(self.bytes.as_mut_ptr() as *mut *mut String, heap) };
+        self.len = SPILLED;
+    }
+    #[cfg(test)]
+    fn is_spilled(&self) -> bool {
+        self.len == SPILLED
+    }
+}
+
+impl<const N: usize> Clone for Buffer<N> {
+    fn clone(&self) -> Buffer<N> {
+        match self.heap_ptr() {
+            None => Buffer { len: self.len, bytes: self.bytes },
+            Some(_) => {
+                let mut result = Buffer::new();
+                result.spill(String::from(&**self));
+                result
+            }
+        }
+    }
+}
+
+impl<const N: usize> Drop for Buffer<N> {
+    fn drop(&mut self) {
+        if let Some(heap) = self.heap_ptr() {
+            // SAFETY: The buffer owns the box, and forgets it here.
+            drop(unsafe { Box::from_raw(heap) });
+        }
+    }
+}
+
+impl<const N: usize> ops::Deref for Buffer<N> {
+    type Target = str;
+    fn deref(&self) -> &str {
+        match self.heap_ptr() {
+            // SAFETY: The box lives as long as the buffer.
+            Some(heap) => unsafe { &*heap },
+            // SAFETY: The bytes are only ever filled with whole strings.
+            None => unsafe { str::from_utf8_unchecked(&self.bytes[..self.len as usize]) },
+        }
+    }
+}
+
+impl<const N: usize> fmt::Write for Buffer<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if let Some(heap) = self.heap_ptr() {
+            // SAFETY: The box is owned by the buffer, borrowed mutably.
+            unsafe { (*heap).push_str(s) };
+            return Ok(());
+        }
+        let len = self.len as usize;
+        if s.len() <= N - len {
+            self.bytes[len..len + s.len()].copy_from_slice(s.as_bytes());
+            self.len += s.len() as u8;
+            return Ok(());
+        }
+        let mut heap = String::with_capacity(len + s.len());
+        heap.push_str(self);
+        heap.push_str(s);
+        self.spill(heap);
+        Ok(())
+    }
+}
 
 /// A single line of hexdump output.
 ///
 /// Can be printed using the `{}` (`std::fmt::Display`) formatter.
+///
+/// Previously captured data and summary lines can be parsed back using
+/// `str::parse`, after which `offset` and `data` reconstruct the dumped
+/// data.
+///
+/// Lines of up to `N` bytes, 64 by default, are stored inline, longer ones
+/// on the heap. See `Hexdump::with_inline_capacity` for wide layouts.
 #[derive(Clone)]
-pub struct Line {
-    inner: BufferImpl,
+pub struct Line<const N: usize = BUFFER_LENGTH> {
+    inner: Buffer<N>,
+}
+
+impl<const N: usize> Line<N> {
+    pub(crate) fn new(inner: Buffer<N>) -> Line<N> {
+        Line { inner }
+    }
+    pub(crate) fn prefixed(&self, prefix: &str) -> Line<N> {
+        let mut buf = Buffer::new();
+        buf.write_str(prefix).unwrap();
+        buf.write_str(self).unwrap();
+        Line::new(buf)
+    }
+    /// Returns the offset shown at the end of a data or summary line.
+    ///
+    /// For the summary line, this is the total length of the dumped data.
+    /// Returns `None` for all other lines.
+    pub fn offset(&self) -> Option<usize> {
+        parse_data_line(self).map(|(offset, _)| offset).or_else(|| parse_summary_line(self))
+    }
+    /// Returns the bytes shown in a data line.
+    ///
+    /// Returns `None` for all other lines, and for data lines without both
+    /// the data and the text column in the default order, see
+    /// `Config::columns`.
+    pub fn data(&self) -> Option<Vec<u8>> {
+        parse_data_line(self).map(|(_, bytes)| bytes)
+    }
+}
+
+/// Error returned when parsing a `Line` fails.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseLineError {
+    _unused: (),
 }
 
-impl Line {
-    fn new(inner: BufferImpl) -> Line {
-        Line { inner: inner }
+impl fmt::Display for ParseLineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("invalid hexdump line")
+    }
+}
+
+impl error::Error for ParseLineError {}
+
+impl<const N: usize> str::FromStr for Line<N> {
+    type Err = ParseLineError;
+    fn from_str(s: &str) -> Result<Line<N>, ParseLineError> {
+        if parse_data_line(s).is_none() && parse_summary_line(s).is_none() {
+            return Err(ParseLineError { _unused: () });
+        }
+        let mut buf = Buffer::new();
+        buf.write_str(s).unwrap();
+        Ok(Line::new(buf))
+    }
+}
+
+fn parse_offset(s: &str) -> Option<usize> {
+    if s.len() < 8 || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    usize::from_str_radix(s, 16).ok()
+}
+
+fn parse_summary_line(line: &str) -> Option<usize> {
+    if !line.starts_with(' ') {
+        return None;
+    }
+    let line = line.trim_start_matches(' ');
+    // Strip the size, see `Config::summary_size`.
+    let line = match line.find("  (") {
+        Some(i) if line.ends_with(')') => &line[..i],
+        _ => line,
+    };
+    parse_offset(line)
+}
+
+fn parse_data_line(line: &str) -> Option<(usize, Vec<u8>)> {
+    let line = line.trim_start_matches(' ');
+    let line = line.strip_prefix('|')?;
+    let end = line.find('|')?;
+    let (hex, rest) = (&line[..end], &line[end + 1..]);
+    let rest = rest.strip_prefix(' ')?;
+    let space = rest.rfind(' ')?;
+    let (text, offset) = (&rest[..space], parse_offset(&rest[space + 1..])?);
+
+    let mut bytes = Vec::new();
+    for segment in hex.split(' ') {
+        if segment.is_empty() || segment.len() % 2 != 0 {
+            return None;
+        }
+        for i in (0..segment.len()).step_by(2) {
+            let digits = segment.get(i..i + 2)?;
+            if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return None;
+            }
+            bytes.push(u8::from_str_radix(digits, 16).ok()?);
+        }
+    }
+
+    let sanitized: String = bytes.iter().map(|&b| sanitize_byte(b)).collect();
+    let text = text.trim_matches(' ');
+    let sanitized = sanitized.trim_matches(' ');
+    // Allow other placeholders than dots, see `Config::placeholder`.
+    if text.chars().count() != sanitized.len()
+        || !text.chars().zip(sanitized.chars()).all(|(t, s)| t == s || s == '.' && !t.is_ascii())
+    {
+        return None;
     }
+    Some((offset, bytes))
 }
 
-impl fmt::Display for Line {
+impl<const N: usize> fmt::Display for Line<N> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         (**self).fmt(f)
     }
 }
 
-impl fmt::Debug for Line {
+impl<const N: usize> fmt::Debug for Line<N> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         (**self).fmt(f)
     }
 }
 
-impl ops::Deref for Line {
+impl<const N: usize> ops::Deref for Line<N> {
     type Target = str;
     fn deref(&self) -> &str {
         &self.inner
     }
 }
 
+impl<const N: usize> AsRef<str> for Line<N> {
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl<const N: usize> borrow::Borrow<str> for Line<N> {
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+impl<const N: usize> PartialEq for Line<N> {
+    fn eq(&self, other: &Line<N>) -> bool {
+        **self == **other
+    }
+}
+
+impl<const N: usize> Eq for Line<N> {}
+
+impl<const N: usize> PartialEq<str> for Line<N> {
+    fn eq(&self, other: &str) -> bool {
+        **self == *other
+    }
+}
+
+impl<'a, const N: usize> PartialEq<&'a str> for Line<N> {
+    fn eq(&self, other: &&'a str) -> bool {
+        **self == **other
+    }
+}
+
+impl<const N: usize> PartialEq<Line<N>> for str {
+    fn eq(&self, other: &Line<N>) -> bool {
+        *self == **other
+    }
+}
+
+impl<const N: usize> PartialEq<Line<N>> for &str {
+    fn eq(&self, other: &Line<N>) -> bool {
+        **self == **other
+    }
+}
+
+impl<const N: usize> hash::Hash for Line<N> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
 /// Return type of `hexdump_iter`.
-pub struct Hexdump<'a> {
-    len: usize,
-    chunks: iter::Enumerate<slice::Chunks<'a, u8>>,
-    summary_done: bool,
+///
+/// Lines are formatted on demand, so random access using `line_at` is cheap.
+/// `std::ops::Index` cannot be implemented as the lines are not stored.
+///
+/// Yields `Line`s with the default inline capacity, see
+/// `with_inline_capacity` for wide layouts.
+#[derive(Clone)]
+pub struct Hexdump<'a, const N: usize = BUFFER_LENGTH> {
+    bytes: Input<'a>,
+    config: Config,
+    start: usize,
+    end: usize,
+    /// Positions among the body lines followed by extra lines, the number
+    /// of these lines and the number of extra lines at earlier positions,
+    /// sorted by position.
+    extras: Vec<(usize, usize, usize)>,
+    /// Chunks covered by the labeled ranges and the index of the range,
+    /// see `annotation`, sorted by chunk.
+    annotated: Vec<(usize, usize)>,
+    /// First chunk and number of chunks of each collapsed run, see
+    /// `Config::collapse_runs`.
+    runs: Vec<(usize, usize)>,
+    /// Lines following the title, see `Config::overview`.
+    overview: Vec<Line>,
+    /// Lines following the summary line, see `Config::statistics`.
+    trailer: Vec<Line>,
+    /// Whether full chunks can be written by `write_plain_chunk`.
+    plain: bool,
+    front: usize,
+    back: usize,
+}
+
+/// The bytes dumped by a `Hexdump`.
+#[derive(Clone)]
+pub(crate) enum Input<'a> {
+    Borrowed(&'a [u8]),
+    /// Bytes owned by the iterator, see `Config::hexdump_owned_iter`.
+    Owned(sync::Arc<[u8]>),
+    /// A memory-mapped file, see `Config::hexdump_file_iter`.
+    #[cfg(feature = "mmap")]
+    Mapped(sync::Arc<memmap2::Mmap>),
+}
+
+impl<'a> ops::Deref for Input<'a> {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        match *self {
+            Input::Borrowed(bytes) => bytes,
+            Input::Owned(ref bytes) => bytes,
+            #[cfg(feature = "mmap")]
+            Input::Mapped(ref map) => map,
+        }
+    }
 }
 
 /// Sanitizes a byte for safe output.
@@ -60,7 +379,7 @@ pub struct Hexdump<'a> {
 /// Any printable ASCII character is returned verbatim (including the space
 /// character `' '`), for all other bytes, an ASCII dot `'.'` is returned.
 pub fn sanitize_byte(byte: u8) -> char {
-    if 0x20 <= byte && byte < 0x7f {
+    if (0x20..0x7f).contains(&byte) {
         byte as char
     } else {
         '.'
@@ -68,154 +387,1429 @@ pub fn sanitize_byte(byte: u8) -> char {
 }
 
 /// Prints a hexdump of the given bytes to stdout.
+///
+/// # Panics
+///
+/// Panics if writing to stdout fails, see `try_hexdump`.
 pub fn hexdump(bytes: &[u8]) {
-    for s in hexdump_iter(bytes) {
-        println!("{}", s);
-    }
+    Config::new().hexdump(bytes)
+}
+
+/// Prints a hexdump of the given bytes to stderr.
+///
+/// Useful for diagnostic dumps that mustn't end up in the program's regular
+/// output. See also `Config::sink`.
+///
+/// # Panics
+///
+/// Panics if writing to stderr fails.
+pub fn hexdump_eprint(bytes: &[u8]) {
+    Config::new().sink(Sink::Stderr).hexdump(bytes)
+}
+
+/// Prints a hexdump of the given bytes to stdout, returning an error if
+/// that fails.
+///
+/// See `Config::try_hexdump` for details.
+///
+/// # Example
+///
+/// ```no_run
+/// extern crate hexdump;
+/// use std::io;
+///
+/// match hexdump::try_hexdump(b"abc") {
+///     Err(ref e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+///     result => result.unwrap(),
+/// }
+/// ```
+pub fn try_hexdump(bytes: &[u8]) -> io::Result<()> {
+    Config::new().try_hexdump(bytes)
+}
+
+/// Writes a hexdump of the given bytes to the given writer.
+pub fn hexdump_write<W: io::Write>(writer: W, bytes: &[u8]) -> io::Result<()> {
+    Config::new().write(writer, bytes)
+}
+
+/// Returns the hexdump of the given bytes as a string.
+///
+/// Each line, including the last one, is terminated by `'\n'`. Useful for
+/// snapshot tests, as the output format is stable.
+pub fn hexdump_string(bytes: &[u8]) -> String {
+    Config::new().hexdump_string(bytes)
+}
+
+/// Returns the lines of a hexdump of the given bytes, without line
+/// terminators.
+pub fn hexdump_lines(bytes: &[u8]) -> Vec<String> {
+    Config::new().hexdump_lines(bytes)
+}
+
+/// Calls `f` with each line of a hexdump of the given bytes.
+///
+/// See `Hexdump::for_each_line` for details.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+///
+/// let mut width = 0;
+/// hexdump::hexdump_for_each(b"abc", |line| width = width.max(line.len()));
+/// assert_eq!(width, 63);
+/// ```
+pub fn hexdump_for_each<F: FnMut(&str)>(bytes: &[u8], f: F) {
+    hexdump_iter(bytes).for_each_line(f);
 }
 
 /// Creates a hexdump iterator that yields the individual lines.
-pub fn hexdump_iter(bytes: &[u8]) -> Hexdump {
-    Hexdump::new(bytes)
+pub fn hexdump_iter(bytes: &[u8]) -> Hexdump<'_> {
+    Hexdump::new(bytes, Config::new())
+}
+
+/// Creates a hexdump iterator that owns the given bytes.
+///
+/// See `Config::hexdump_owned_iter` for details.
+pub fn hexdump_owned_iter(bytes: Vec<u8>) -> Hexdump<'static> {
+    Config::new().hexdump_owned_iter(bytes)
+}
+
+/// Creates a hexdump iterator that only yields the lines covering the given
+/// byte range.
+///
+/// See `Config::hexdump_range_iter` for details.
+pub fn hexdump_range_iter(bytes: &[u8], range: ops::Range<usize>) -> Hexdump<'_> {
+    Config::new().hexdump_range_iter(bytes, range)
+}
+
+/// Creates a hexdump iterator over the UTF-8 encoding of the given string
+/// that marks the bytes of each multi-byte character.
+///
+/// See `Config::hexdump_str_iter` for details.
+pub fn hexdump_str_iter(s: &str) -> Hexdump<'_> {
+    Config::new().hexdump_str_iter(s)
+}
+
+/// Creates a hexdump iterator that only yields the lines covering the last
+/// `len` bytes.
+///
+/// See `Config::hexdump_tail_iter` for details.
+pub fn hexdump_tail_iter(bytes: &[u8], len: usize) -> Hexdump<'_> {
+    Config::new().hexdump_tail_iter(bytes, len)
 }
 
 impl<'a> Hexdump<'a> {
-    fn new(bytes: &[u8]) -> Hexdump {
-        Hexdump {
-            len: bytes.len(),
-            chunks: bytes.chunks(CHUNK_LENGTH).enumerate(),
-            summary_done: false,
+    pub(crate) fn new(bytes: &'a [u8], config: Config) -> Hexdump<'a> {
+        Hexdump::with_range(bytes, config, 0..bytes.len())
+    }
+    pub(crate) fn with_range(bytes: &'a [u8], config: Config, range: ops::Range<usize>)
+        -> Hexdump<'a>
+    {
+        Hexdump::from_input(Input::Borrowed(bytes), config, range)
+    }
+    pub(crate) fn from_input(bytes: Input<'a>, config: Config, range: ops::Range<usize>)
+        -> Hexdump<'a>
+    {
+        assert!(range.start <= range.end && range.end <= bytes.len(),
+                "range {:?} out of bounds for {} bytes", range, bytes.len());
+        // No displayed offset is larger than the one of the end.
+        config.end_offset(range.end);
+        let mut result = Hexdump {
+            bytes,
+            config,
+            start: range.start,
+            end: range.end,
+            extras: Vec::new(),
+            annotated: Vec::new(),
+            runs: Vec::new(),
+            overview: Vec::new(),
+            trailer: Vec::new(),
+            plain: false,
+            front: 0,
+            back: 0,
+        };
+        result.plain = is_plain(&result.config);
+        result.runs = result.compute_runs();
+        result.annotated = result.compute_annotated();
+        result.extras = result.compute_extras();
+        if result.config.overview {
+            result.overview = Overview::new(&result.bytes[result.start..result.end],
+                                            &result.config, result.start)
+                .collect();
         }
+        result.trailer = result.compute_trailer();
+        result.back = result.num_lines();
+        result
     }
 }
 
-fn once<T,F:FnOnce()->T>(once: &mut bool, f: F) -> Option<T> {
-    if !*once {
-        *once = true;
-        Some(f())
-    } else {
-        None
+impl<'a, const N: usize> Hexdump<'a, N> {
+    /// Changes the number of bytes up to which yielded lines are stored
+    /// inline, without allocating.
+    ///
+    /// The default of 64 bytes fits regular lines of 16 bytes. Wider
+    /// layouts, e.g. 32 bytes per line or extra columns, need a larger
+    /// capacity to avoid an allocation per line.
+    ///
+    /// The capacity must be at least the size of a pointer and below 255
+    /// bytes, creating lines panics otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// extern crate hexdump;
+    ///
+    /// let bytes = [0; 64];
+    /// let config = hexdump::Config::new().bytes_per_line(32);
+    /// let lines: Vec<hexdump::Line<128>> = config.hexdump_iter(&bytes)
+    ///     .with_inline_capacity::<128>()
+    ///     .collect();
+    /// assert_eq!(lines.len(), 3);
+    /// ```
+    pub fn with_inline_capacity<const M: usize>(self) -> Hexdump<'a, M> {
+        Hexdump {
+            bytes: self.bytes,
+            config: self.config,
+            start: self.start,
+            end: self.end,
+            extras: self.extras,
+            annotated: self.annotated,
+            runs: self.runs,
+            overview: self.overview,
+            trailer: self.trailer,
+            plain: self.plain,
+            front: self.front,
+            back: self.back,
+        }
+    }
+    /// Converts the iterator into one that owns its input, so it can be
+    /// returned from the function creating the input or moved to another
+    /// thread.
+    ///
+    /// Copies the input unless it's already owned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// extern crate hexdump;
+    ///
+    /// fn dump_message(id: u8) -> hexdump::Hexdump<'static> {
+    ///     let message = [0xca, 0xfe, id];
+    ///     hexdump::hexdump_iter(&message).into_owned()
+    /// }
+    ///
+    /// assert_eq!(dump_message(1).count(), 2);
+    /// ```
+    pub fn into_owned(self) -> Hexdump<'static, N> {
+        let bytes = match self.bytes {
+            Input::Borrowed(bytes) => Input::Owned(bytes.into()),
+            Input::Owned(bytes) => Input::Owned(bytes),
+            #[cfg(feature = "mmap")]
+            Input::Mapped(map) => Input::Mapped(map),
+        };
+        Hexdump {
+            bytes,
+            config: self.config,
+            start: self.start,
+            end: self.end,
+            extras: self.extras,
+            annotated: self.annotated,
+            runs: self.runs,
+            overview: self.overview,
+            trailer: self.trailer,
+            plain: self.plain,
+            front: self.front,
+            back: self.back,
+        }
+    }
+    /// Returns the `index`th of the remaining lines without advancing the
+    /// iterator.
+    ///
+    /// Only the requested line is formatted. Returns `None` if `index` is
+    /// out of bounds.
+    pub fn line_at(&self, index: usize) -> Option<Line<N>> {
+        if index < self.len() {
+            Some(self.line(self.front + index))
+        } else {
+            None
+        }
+    }
+    /// Splits the remaining lines into the first `index` lines and the
+    /// rest, returning an iterator over each.
+    ///
+    /// The lines are the same as if they were yielded by this iterator, so
+    /// the halves can be formatted independently, e.g. on different
+    /// threads or as separate pages.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of remaining lines.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// extern crate hexdump;
+    ///
+    /// let bytes = [0; 100];
+    /// let (first, second) = hexdump::hexdump_iter(&bytes).split_at(3);
+    /// let lines: Vec<_> = first.chain(second).collect();
+    /// assert_eq!(lines, hexdump::hexdump_iter(&bytes).collect::<Vec<_>>());
+    /// ```
+    pub fn split_at(self, index: usize) -> (Hexdump<'a, N>, Hexdump<'a, N>) {
+        assert!(index <= self.len(), "split index {} out of bounds for {} lines",
+                index, self.len());
+        let mut first = self.clone();
+        let mut second = self;
+        first.back = first.front + index;
+        second.front = first.back;
+        (first, second)
+    }
+    /// Calls `f` with each of the remaining lines, consuming the iterator.
+    ///
+    /// All lines are formatted into the same buffer, so unlike iterating,
+    /// this doesn't copy each line into a new `Line`.
+    pub fn for_each_line<F: FnMut(&str)>(self, mut f: F) {
+        let mut buf = String::with_capacity(BUFFER_LENGTH);
+        for index in self.front..self.back {
+            buf.clear();
+            self.write_line(index, &mut buf).unwrap();
+            f(&buf);
+            self.report_progress(index, false);
+        }
+    }
+    fn chunk_length(&self) -> usize {
+        self.config.chunk_length()
+    }
+    /// Returns the index of the first chunk, counted from the start of the
+    /// input.
+    fn first_chunk(&self) -> usize {
+        (self.start + self.shift()) / self.chunk_length()
+    }
+    /// Returns the number of positions the bytes are shifted by to align
+    /// the lines to the base offset, see `Config::align_base_offset`.
+    fn shift(&self) -> usize {
+        if self.config.align_base_offset {
+            self.config.base_offset % self.chunk_length()
+        } else {
+            0
+        }
+    }
+    /// Returns the chunk containing the byte at the given position.
+    fn chunk_of(&self, position: usize) -> usize {
+        (position + self.shift()) / self.chunk_length() - self.first_chunk()
+    }
+    fn num_chunks(&self) -> usize {
+        if self.start == self.end {
+            return 0;
+        }
+        (self.end + self.shift()).div_ceil(self.chunk_length()) - self.first_chunk()
+    }
+    /// Returns the number of rows, i.e. chunks with each collapsed run
+    /// counting as one.
+    fn num_rows(&self) -> usize {
+        let collapsed: usize = self.runs.iter().map(|&(_, count)| count - 1).sum();
+        self.num_chunks() - collapsed
+    }
+    /// Returns the first chunk of the given row.
+    fn row_chunk(&self, row: usize) -> usize {
+        let mut chunk = row;
+        for &(first, count) in &self.runs {
+            if first >= chunk {
+                break;
+            }
+            chunk += count - 1;
+        }
+        chunk
+    }
+    /// Returns the row containing the given chunk.
+    fn chunk_row(&self, chunk: usize) -> usize {
+        let mut row = chunk;
+        for &(first, count) in &self.runs {
+            if first + count <= chunk {
+                row -= count - 1;
+            } else if first <= chunk {
+                row -= chunk - first;
+            }
+        }
+        row
+    }
+    /// Returns the number of rows shown before and after the omission
+    /// marker, or `None` if nothing is omitted.
+    fn elision(&self) -> Option<(usize, usize)> {
+        let tail = self.config.tail_lines;
+        self.config.max_lines
+            .filter(|&head| head.saturating_add(tail) < self.num_rows())
+            .map(|head| (head, tail))
     }
+    fn num_lines(&self) -> usize {
+        let title = if self.config.title.is_some() { 1 } else { 0 };
+        let extras = self.extras.last().map_or(0, |&(_, count, before)| before + count);
+        let frame = if self.config.frame { 4 } else { 0 };
+        let ruler = if self.config.ruler && !self.config.frame { 1 } else { 0 };
+        title + self.overview.len() + frame + ruler + self.body_len() + extras + 1
+            + self.trailer.len()
+    }
+    /// Returns whether the given chunk is shown, i.e. neither omitted nor
+    /// collapsed.
+    fn is_visible(&self, chunk: usize) -> bool {
+        if self.runs.iter().any(|&(first, count)| first <= chunk && chunk < first + count) {
+            return false;
+        }
+        let row = self.chunk_row(chunk);
+        match self.elision() {
+            Some((head, tail)) => row < head || row >= self.num_rows() - tail,
+            None => row < self.num_rows(),
+        }
+    }
+    /// Returns the position of the given visible chunk among the body lines,
+    /// not counting extra lines.
+    fn chunk_position(&self, chunk: usize) -> usize {
+        let row = self.chunk_row(chunk);
+        match self.elision() {
+            Some((head, tail)) if row >= head => head + 1 + row + tail - self.num_rows(),
+            _ => row,
+        }
+    }
+    fn compute_runs(&self) -> Vec<(usize, usize)> {
+        let min_len = match self.config.collapse_runs {
+            Some(min_len) => min_len,
+            None => return Vec::new(),
+        };
+        let mut result = Vec::new();
+        // The current run: first chunk, number of chunks, byte and length.
+        let mut current: Option<(usize, usize, u8, usize)> = None;
+        for chunk in 0..self.num_chunks() + 1 {
+            let start = self.chunk_start(chunk);
+            let end = self.chunk_start(chunk + 1);
+            let bytes = &self.bytes[start..end];
+            // Redacted bytes must not be summarized by their value.
+            let uniform = bytes.first()
+                .filter(|&&b| bytes.iter().all(|&x| x == b))
+                .filter(|_| !(start..end).any(|i| self.config.is_redacted(i)))
+                .cloned();
+            match (current, uniform) {
+                (Some((first, count, byte, len)), Some(b)) if b == byte => {
+                    current = Some((first, count + 1, byte, len + bytes.len()));
+                    continue;
+                }
+                (Some((first, count, _, len)), _) => {
+                    if count >= 2 && len >= min_len {
+                        result.push((first, count));
+                    }
+                }
+                (None, _) => {}
+            }
+            current = uniform.map(|b| (chunk, 1, b, bytes.len()));
+        }
+        result
+    }
+    fn compute_annotated(&self) -> Vec<(usize, usize)> {
+        let num_fields = self.config.layout.as_ref().map_or(0, |layout| layout.fields().len());
+        let mut result = Vec::new();
+        for index in 0..self.config.annotations.len() + num_fields {
+            let range = self.annotation(index).0;
+            let start = cmp::max(range.start, self.start);
+            let end = cmp::min(range.end, self.end);
+            if start < end {
+                result.extend((self.chunk_of(start)..self.chunk_of(end - 1) + 1)
+                    .map(|chunk| (chunk, index)));
+            }
+        }
+        result.sort_unstable();
+        result
+    }
+    fn compute_extras(&self) -> Vec<(usize, usize, usize)> {
+        let mut chunks: Vec<usize> = self.config.inspect.iter()
+            .filter(|&&o| self.start <= o && o < self.end)
+            .map(|&o| self.chunk_of(o))
+            .collect();
+        chunks.extend(self.annotated.iter().map(|&(chunk, _)| chunk));
+        chunks.sort_unstable();
+        chunks.dedup();
+        chunks.retain(|&c| self.is_visible(c));
+        let mut positions: Vec<usize> = chunks.into_iter()
+            .map(|c| self.chunk_position(c))
+            .collect();
+        if let Some(every) = self.config.ruler_every {
+            positions.extend((every..self.body_len()).step_by(every).map(|p| p - 1));
+        }
+        positions.sort_unstable();
+        positions.dedup();
+        let mut before = 0;
+        positions.into_iter()
+            .filter_map(|p| {
+                let count = self.inserted_lines(p).len();
+                before += count;
+                Some((p, count, before - count)).filter(|_| count != 0)
+            })
+            .collect()
+    }
+    /// Returns the number of body lines, not counting extra lines.
+    fn body_len(&self) -> usize {
+        match self.elision() {
+            Some((head, tail)) => head + 1 + tail,
+            None => self.num_rows(),
+        }
+    }
+    /// Returns the row shown at the given position among the body lines,
+    /// or `None` for the omission marker.
+    fn position_row(&self, position: usize) -> Option<usize> {
+        match self.elision() {
+            Some((head, _)) if position < head => Some(position),
+            Some((head, _)) if position == head => None,
+            Some((head, tail)) => Some(self.num_rows() - tail + (position - head - 1)),
+            None => Some(position),
+        }
+    }
+    /// Formats the extra lines shown after the given body line.
+    fn inserted_lines(&self, position: usize) -> Vec<Line> {
+        let chunk = self.position_row(position)
+            .map(|row| self.row_chunk(row))
+            .filter(|&chunk| self.is_visible(chunk));
+        let mut result: Vec<_> = match chunk {
+            Some(chunk) => self.extra_lines(chunk).into_iter()
+                .map(|line| frame::wrap(&self.config, line))
+                .collect(),
+            None => Vec::new(),
+        };
+        if let Some(every) = self.config.ruler_every {
+            let next = position + 1;
+            if next.is_multiple_of(every) && next < self.body_len() {
+                result.push(hexdump_ruler(&self.config));
+            }
+        }
+        result
+    }
+    fn compute_trailer(&self) -> Vec<Line> {
+        if !self.config.statistics {
+            return Vec::new();
+        }
+        let mut statistics = Statistics::new();
+        for i in self.start..self.end {
+            if !self.config.is_redacted(i) {
+                statistics.add(self.bytes[i]);
+            }
+        }
+        statistics.lines()
+    }
+    /// Returns the labeled range with the given index, counting the fields
+    /// of the layout after the annotations.
+    fn annotation(&self, index: usize) -> (ops::Range<usize>, borrow::Cow<'_, str>) {
+        let annotations = &self.config.annotations;
+        if let Some((range, label)) = annotations.get(index) {
+            return (range.clone(), borrow::Cow::Borrowed(label));
+        }
+        let layout = self.config.layout.as_ref().expect("annotation index out of bounds");
+        let f = &layout.fields()[index - annotations.len()];
+        let label = if f.range().any(|i| self.config.is_redacted(i)) {
+            format!("{} = <redacted>", f.name)
+        } else {
+            f.label(&self.bytes)
+        };
+        (f.range(), borrow::Cow::Owned(label))
+    }
+    /// Formats the extra lines shown beneath the given chunk.
+    fn extra_lines(&self, chunk: usize) -> Vec<Line> {
+        let line_start = self.chunk_start(chunk);
+        let line_end = self.chunk_start(chunk + 1);
+        let lead = self.chunk_lead(chunk);
+        let mut result = Vec::new();
+        let first = self.annotated.partition_point(|&(c, _)| c < chunk);
+        let annotated = self.annotated[first..].iter().take_while(|&&(c, _)| c == chunk);
+        for &(_, index) in annotated {
+            let (range, label) = self.annotation(index);
+            let start = cmp::max(range.start, line_start);
+            let end = cmp::min(range.end, line_end);
+            if start < end {
+                let continued = start > range.start;
+                result.push(hexdump_annotation(&self.config, start - line_start + lead,
+                                               end - line_start + lead, &label, continued));
+            }
+        }
+        for &offset in &self.config.inspect {
+            if line_start <= offset && offset < line_end {
+                let displayed = self.displayed_offset(offset);
+                let column = data_column(&self.config, offset - line_start + lead);
+                let end = (offset..self.end).find(|&i| self.config.is_redacted(i))
+                    .unwrap_or(self.end);
+                result.extend(inspect::inspect(&self.bytes[offset..end], displayed, column));
+            }
+        }
+        result
+    }
+    /// Returns the byte offset at which the given chunk starts, clamped to
+    /// the dumped range.
+    fn chunk_start(&self, index: usize) -> usize {
+        let shift = self.shift();
+        let offset = (self.first_chunk() + index) * self.chunk_length();
+        cmp::min(cmp::max(offset, self.start + shift), self.end + shift) - shift
+    }
+    /// Returns the number of blank positions at the start of the given
+    /// chunk's line.
+    fn chunk_lead(&self, index: usize) -> usize {
+        let offset = (self.first_chunk() + index) * self.chunk_length();
+        self.chunk_start(index) + self.shift() - offset
+    }
+    /// Returns the offset as it is shown in the dump.
+    fn displayed_offset(&self, offset: usize) -> usize {
+        let offset = self.config.base_offset + offset;
+        if self.config.normalize_offsets {
+            offset - self.first_chunk() * self.chunk_length()
+        } else {
+            offset
+        }
+    }
+    fn write_chunk<W: fmt::Write>(&self, index: usize, buf: &mut W) -> fmt::Result {
+        let start = self.chunk_start(index);
+        let end = self.chunk_start(index + 1);
+        let lead = self.chunk_lead(index);
+        if self.plain && lead == 0 && end - start == CHUNK_LENGTH {
+            return write_plain_chunk(buf, &self.config, self.displayed_offset(start),
+                                     &self.bytes[start..end]);
+        }
+        write_chunk(buf, &self.config, self.displayed_offset(start) - lead, lead,
+                    start, &self.bytes, start..end)
+    }
+    /// Writes the given row, either a chunk or a collapsed run.
+    fn write_row<W: fmt::Write>(&self, row: usize, buf: &mut W) -> fmt::Result {
+        let chunk = self.row_chunk(row);
+        match self.runs.iter().find(|&&(first, _)| first == chunk) {
+            Some(&(first, count)) => {
+                let start = self.chunk_start(first);
+                let end = self.chunk_start(first + count);
+                let mut run = Buffer::new();
+                write!(run, "** {:#04x} x {} bytes ({:#x}..{:#x}) **", self.bytes[start],
+                       end - start, self.displayed_offset(start), self.displayed_offset(end))?;
+                buf.write_str(&frame::wrap(&self.config, Line::new(run)))
+            }
+            None => self.write_chunk(chunk, buf),
+        }
+    }
+    fn line(&self, index: usize) -> Line<N> {
+        let mut buf = Buffer::new();
+        self.write_line(index, &mut buf).unwrap();
+        Line::new(buf)
+    }
+    fn write_line<W: fmt::Write>(&self, index: usize, buf: &mut W) -> fmt::Result {
+        match self.item(index) {
+            Item::Trailer(i) => buf.write_str(&self.trailer[i]),
+            Item::Bottom => buf.write_str(&frame::bottom(&self.config)),
+            Item::Title => {
+                let title = self.config.title.as_ref().unwrap();
+                buf.write_str(&hexdump_title(title, self.end - self.start))
+            }
+            Item::Overview(i) => buf.write_str(&self.overview[i]),
+            Item::Top => buf.write_str(&frame::top(&self.config)),
+            Item::Header => buf.write_str(&frame::header(&self.config)),
+            Item::Separator => buf.write_str(&frame::separator(&self.config)),
+            Item::Ruler => buf.write_str(&hexdump_ruler(&self.config)),
+            Item::Inserted(position, i) => {
+                buf.write_str(&self.inserted_lines(position).swap_remove(i))
+            }
+            Item::Row(row) => self.write_row(row, buf),
+            Item::Omitted => {
+                let (head, tail) = self.elision().unwrap();
+                let omitted = self.chunk_start(self.row_chunk(self.num_rows() - tail))
+                    - self.chunk_start(self.row_chunk(head));
+                buf.write_str(&frame::wrap(&self.config, hexdump_omitted(omitted)))
+            }
+            Item::Summary => {
+                buf.write_str(&hexdump_summary(&self.config, self.displayed_offset(self.end),
+                                               self.end - self.start))
+            }
+        }
+    }
+    /// Returns what the line with the given index shows.
+    fn item(&self, mut index: usize) -> Item {
+        let end = self.num_lines() - self.trailer.len();
+        if index >= end {
+            return Item::Trailer(index - end);
+        }
+        if self.config.frame && index == end - 1 {
+            return Item::Bottom;
+        }
+        if self.config.title.is_some() {
+            if index == 0 {
+                return Item::Title;
+            }
+            index -= 1;
+        }
+        if index < self.overview.len() {
+            return Item::Overview(index);
+        }
+        index -= self.overview.len();
+        if self.config.frame {
+            match index {
+                0 => return Item::Top,
+                1 => return Item::Header,
+                2 => return Item::Separator,
+                _ => index -= 3,
+            }
+        }
+        if self.config.ruler && !self.config.frame {
+            if index == 0 {
+                return Item::Ruler;
+            }
+            index -= 1;
+        }
+        // The first position whose extra lines don't end before the line.
+        let next = self.extras
+            .partition_point(|&(position, count, before)| position + before + count < index);
+        index -= match self.extras.get(next) {
+            Some(&(position, _, before)) if index > position + before => {
+                return Item::Inserted(position, index - position - before - 1);
+            }
+            Some(&(_, _, before)) => before,
+            None => self.extras.last().map_or(0, |&(_, count, before)| before + count),
+        };
+        let num_rows = self.num_rows();
+        match self.elision() {
+            Some((head, _)) if index < head => Item::Row(index),
+            Some((head, _)) if index == head => Item::Omitted,
+            Some((head, tail)) if index <= head + tail => {
+                Item::Row(num_rows - tail + (index - head - 1))
+            }
+            Some(_) => Item::Summary,
+            None if index < num_rows => Item::Row(index),
+            None => Item::Summary,
+        }
+    }
+    /// Calls the progress callback if the given line completes a multiple
+    /// of the configured number of rows or the last row, counted from the
+    /// end if iterating backwards.
+    fn report_progress(&self, index: usize, back: bool) {
+        let (every, progress) = match self.config.progress {
+            Some((every, ref progress)) => (every, progress),
+            None => return,
+        };
+        if let Item::Row(row) = self.item(index) {
+            let total = self.end - self.start;
+            if back {
+                if (self.num_rows() - row).is_multiple_of(every) || row == 0 {
+                    let start = self.chunk_start(self.row_chunk(row));
+                    (progress.0)(self.end - start, Some(total));
+                }
+            } else if (row + 1).is_multiple_of(every) || row + 1 == self.num_rows() {
+                let end = self.chunk_start(self.row_chunk(row + 1));
+                (progress.0)(end - self.start, Some(total));
+            }
+        }
+    }
+}
+
+/// The kinds of lines of a `Hexdump`, see `Hexdump::item`.
+enum Item {
+    /// The given line of the trailer.
+    Trailer(usize),
+    Bottom,
+    Title,
+    /// The given line of the overview.
+    Overview(usize),
+    Top,
+    Header,
+    Separator,
+    Ruler,
+    /// The given extra line shown after the body line at the given
+    /// position.
+    Inserted(usize, usize),
+    /// The given row, either a chunk or a collapsed run.
+    Row(usize),
+    Omitted,
+    Summary,
 }
 
-impl<'a> Iterator for Hexdump<'a> {
-    type Item = Line;
-    fn next(&mut self) -> Option<Line> {
-        let summary_done = &mut self.summary_done;
-        let len = self.len;
-        self.chunks.next().map(hexdump_chunk)
-            .or_else(|| once(summary_done, || hexdump_summary(len)))
+impl<'a, const N: usize> Iterator for Hexdump<'a, N> {
+    type Item = Line<N>;
+    fn next(&mut self) -> Option<Line<N>> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        let line = self.line(self.front - 1);
+        self.report_progress(self.front - 1, false);
+        Some(line)
     }
     fn size_hint(&self) -> (usize, Option<usize>) {
         (self.len(), Some(self.len()))
     }
+    fn nth(&mut self, n: usize) -> Option<Line<N>> {
+        let n = cmp::min(n, self.len());
+        for index in self.front..self.front + n {
+            self.report_progress(index, false);
+        }
+        self.front += n;
+        self.next()
+    }
+    fn count(self) -> usize {
+        self.len()
+    }
+    fn last(mut self) -> Option<Line<N>> {
+        self.next_back()
+    }
 }
 
-impl<'a> DoubleEndedIterator for Hexdump<'a> {
-    fn next_back(&mut self) -> Option<Line> {
-        let chunks = &mut self.chunks;
-        let len = self.len;
-        once(&mut self.summary_done, || hexdump_summary(len))
-            .or_else(|| chunks.next_back().map(hexdump_chunk))
+impl<'a, const N: usize> DoubleEndedIterator for Hexdump<'a, N> {
+    fn next_back(&mut self) -> Option<Line<N>> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        let line = self.line(self.back);
+        self.report_progress(self.back, true);
+        Some(line)
+    }
+    fn nth_back(&mut self, n: usize) -> Option<Line<N>> {
+        let n = cmp::min(n, self.len());
+        for index in (self.back - n..self.back).rev() {
+            self.report_progress(index, true);
+        }
+        self.back -= n;
+        self.next_back()
     }
 }
 
-impl<'a> ExactSizeIterator for Hexdump<'a> {
+impl<'a, const N: usize> iter::FusedIterator for Hexdump<'a, N> {}
+
+impl<'a, const N: usize> ExactSizeIterator for Hexdump<'a, N> {
     fn len(&self) -> usize {
-        self.chunks.len() + if !self.summary_done { 1 } else { 0 }
+        self.back - self.front
+    }
+}
+
+/// Returns the labels of the byte positions within a line for the data and
+/// the text column.
+pub(crate) fn ruler_labels(config: &Config) -> (String, String) {
+    let mut data = String::new();
+    let mut text = String::new();
+    for position in 0..config.chunk_length() {
+        while data.len() < cell_column(config, position) {
+            data.push(' ');
+        }
+        if config.byte_format == ByteFormat::Hex {
+            write!(data, "{:02x}", position % 0x100).unwrap();
+        } else {
+            write!(data, "{:>1$x}", position, config.byte_format.width()).unwrap();
+        }
+        while text.len() < text_column(config, position) {
+            text.push(' ');
+        }
+        write!(text, "{:x}", position % 0x10).unwrap();
     }
+    (data, text)
 }
 
-fn hexdump_summary(len: usize) -> Line {
-    let mut buf = BufferImpl::new();
-    buf.write_str("    ").unwrap();
-    for _ in 0..CHUNK_LENGTH {
-        buf.write_str("   ").unwrap();
+/// Formats a line labeling the byte positions above the data and the text
+/// column.
+fn hexdump_ruler(config: &Config) -> Line {
+    if config.frame {
+        return frame::header(config);
     }
-    for _ in 1..NUM_SEGMENTS_PER_CHUNK {
+    let (data, text) = ruler_labels(config);
+    let mut ruler = String::new();
+    for &column in config.columns.order() {
+        match column {
+            Column::Data => {
+                let open = config.delimiters.0.chars().count();
+                let close = config.delimiters.1.chars().count();
+                write!(ruler, "{:open$}{:data_width$}{:close$} ", "", data, "", open = open,
+                       data_width = data_width(config, config.chunk_length()), close = close)
+                    .unwrap();
+            }
+            Column::Text => {
+                write!(ruler, "{:1$} ", text, text_width(config, config.chunk_length())).unwrap()
+            }
+        }
+    }
+    let mut buf = Buffer::new();
+    buf.write_str(ruler.trim_end()).unwrap();
+    Line::new(buf)
+}
+
+fn hexdump_title(title: &str, len: usize) -> Line {
+    let mut buf = Buffer::new();
+    write!(buf, "---- {} ({} bytes) ----", title, len).unwrap();
+    Line::new(buf)
+}
+
+/// Formats a line marking the representation of the bytes at the
+/// positions `start..end` of the preceding data line.
+fn hexdump_annotation(config: &Config, start: usize, end: usize, label: &str, continued: bool)
+    -> Line
+{
+    let mut buf = Buffer::new();
+    let first = data_column(config, start);
+    let last = data_column(config, end - 1) + cell_width(config);
+    for _ in 0..first {
         buf.write_str(" ").unwrap();
     }
-    write!(buf, "{:08x}", len).unwrap();
+    for _ in first..last {
+        buf.write_str("^").unwrap();
+    }
+    write!(buf, " {}", label).unwrap();
+    if continued {
+        buf.write_str(" (cont.)").unwrap();
+    }
+    Line::new(buf)
+}
 
+fn hexdump_omitted(len: usize) -> Line {
+    let mut buf = Buffer::new();
+    write!(buf, "... {} bytes omitted ...", len).unwrap();
     Line::new(buf)
 }
 
-fn hexdump_chunk((i, chunk): (usize, &[u8])) -> Line {
-    let offset = i * CHUNK_LENGTH;
+/// Returns the Shannon entropy of the given bytes in bits per byte.
+///
+/// The result lies between 0 and 8, but can't exceed the binary logarithm
+/// of the number of bytes.
+pub(crate) fn entropy(bytes: &[u8]) -> f64 {
+    let mut counts = [0usize; 256];
+    for &b in bytes {
+        counts[b as usize] += 1;
+    }
+    entropy_of_counts(&counts, bytes.len())
+}
+
+/// Returns the Shannon entropy of `len` bytes, given the number of
+/// occurrences of each byte value.
+pub(crate) fn entropy_of_counts(counts: &[usize; 256], len: usize) -> f64 {
+    let len = len as f64;
+    let sum: f64 = counts.iter().filter(|&&c| c != 0).map(|&c| {
+        let p = c as f64 / len;
+        p * p.log2()
+    }).sum();
+    // Avoid returning negative zero.
+    0.0 - sum
+}
 
-    let mut buf = BufferImpl::new();
-    buf.write_str("|").unwrap();
+/// Returns the 24-bit color of the byte in heatmap mode.
+fn heatmap_color(byte: u8) -> (u8, u8, u8) {
+    let level = (64 + byte as u32 * 191 / 255) as u8;
+    if sanitize_byte(byte) == byte as char {
+        (0, level, level)
+    } else {
+        (level, level, level)
+    }
+}
 
-    let mut first = true;
-    let mut num_segments = 0;
-    let mut num_bytes = 0;
-    for segment in chunk.chunks(SEGMENT_LENGTH) {
-        if first {
-            first = false;
-        } else {
-            buf.write_str(" ").unwrap();
-        }
+fn segment_separator_width(config: &Config) -> usize {
+    config.segment_separator.chars().count()
+}
+
+/// Returns the column of the representation of the byte at the given
+/// position within the data column, i.e. after the opening delimiter.
+pub(crate) fn cell_column(config: &Config, position: usize) -> usize {
+    let format = config.byte_format;
+    (format.width() + format.separator()) * position
+        + position / SEGMENT_LENGTH * segment_separator_width(config)
+}
 
-        num_bytes = 0;
-        for &b in segment {
-            write!(buf, "{:02x}", b).unwrap();
-            num_bytes += 1;
+/// Returns the column of the character for the byte at the given position
+/// within the text column.
+pub(crate) fn text_column(config: &Config, position: usize) -> usize {
+    if config.group_text {
+        position + position / SEGMENT_LENGTH
+    } else {
+        position
+    }
+}
+
+/// Returns the width of the text column of a full line.
+fn text_width(config: &Config, chunk_length: usize) -> usize {
+    match chunk_length {
+        0 => 0,
+        _ => text_column(config, chunk_length - 1) + 1,
+    }
+}
+
+/// Returns the width of the data column of a full line.
+pub(crate) fn data_width(config: &Config, chunk_length: usize) -> usize {
+    let separators = config.byte_format.separator() + segment_separator_width(config);
+    cell_column(config, chunk_length) - separators
+}
+
+/// Returns the width of the given column of a data line, including the
+/// delimiters of the data column.
+pub(crate) fn column_width(config: &Config, column: Column, chunk_length: usize) -> usize {
+    match column {
+        Column::Data if config.frame => data_width(config, chunk_length),
+        Column::Data => {
+            config.delimiters.0.chars().count() + data_width(config, chunk_length)
+                + config.delimiters.1.chars().count()
         }
-        num_segments += 1;
+        Column::Text => text_width(config, chunk_length),
     }
+}
 
-    buf.write_str("| ").unwrap();
-    for _ in num_bytes..SEGMENT_LENGTH {
-        buf.write_str("  ").unwrap();
+/// Returns the width of the separator following each column of a data line.
+fn column_separator_width(config: &Config) -> usize {
+    if config.frame { frame::MIDDLE.chars().count() } else { 1 }
+}
+
+/// Returns the width of the representation of a byte in the column
+/// `data_column` refers to.
+pub(crate) fn cell_width(config: &Config) -> usize {
+    if config.columns.has_data() { config.byte_format.width() } else { 1 }
+}
+
+/// Returns the column of the representation of the byte at the given
+/// position of a data line, in the text column if the data column is
+/// hidden.
+///
+/// In a frame, the column is that in the lines beneath the data lines,
+/// which only have a one-character border, see `frame::wrap`.
+pub(crate) fn data_column(config: &Config, position: usize) -> usize {
+    let has_data = config.columns.has_data();
+    let mut column = if config.frame { 1 } else { 0 };
+    for &c in config.columns.order() {
+        if c == Column::Data || !has_data {
+            break;
+        }
+        column += column_width(config, c, config.chunk_length()) + column_separator_width(config);
     }
-    for _ in num_segments..NUM_SEGMENTS_PER_CHUNK {
-        for _ in 0..SEGMENT_LENGTH {
-            buf.write_str("  ").unwrap();
+    if !has_data {
+        column + text_column(config, position)
+    } else if config.frame {
+        column + cell_column(config, position)
+    } else {
+        column + config.delimiters.0.chars().count() + cell_column(config, position)
+    }
+}
+
+/// Returns the width of a data line for the given number of bytes per line.
+pub(crate) fn line_width(config: &Config, chunk_length: usize) -> usize {
+    // The borders at both ends of a frame.
+    let borders = if config.frame {
+        frame::LEFT.chars().count() + frame::RIGHT.chars().count()
+    } else {
+        0
+    };
+    let columns: usize = config.columns.order().iter()
+        .map(|&column| column_width(config, column, chunk_length) + column_separator_width(config))
+        .sum();
+    borders + columns + offset_width(config)
+}
+
+/// Returns the minimum width of the offset column.
+pub(crate) fn offset_width(config: &Config) -> usize {
+    // Relative offsets have a sign.
+    8 + config.anchor.is_some() as usize
+}
+
+/// Writes an offset as shown in the offset column.
+pub(crate) fn write_offset<W: fmt::Write>(buf: &mut W, config: &Config, offset: usize) {
+    match config.anchor {
+        Some(anchor) if offset >= anchor => write!(buf, "+{:08x}", offset - anchor),
+        Some(anchor) => write!(buf, "-{:08x}", anchor - offset),
+        None => write!(buf, "{:08x}", offset),
+    }.unwrap();
+}
+
+/// Returns the offset, the bytes and the text of the data line of `chunk`
+/// at `offset` as separate fields, for output formats with columns of their
+/// own.
+pub(crate) fn line_fields(config: &Config, offset: usize, chunk: &[u8])
+    -> (String, String, String)
+{
+    let redacted = |i: usize| config.is_redacted(offset + i);
+    let mut position = String::new();
+    write_offset(&mut position, config, config.base_offset + offset);
+    let mut data = String::new();
+    let mut cell = String::new();
+    for (i, &b) in chunk.iter().enumerate() {
+        if i != 0 {
+            data.push(' ');
+        }
+        if redacted(i) {
+            data.extend(iter::repeat_n('x', config.byte_format.width()));
+        } else {
+            cell.clear();
+            config.byte_format.write(&mut cell, b, config.uppercase).unwrap();
+            data.push_str(cell.trim_start());
         }
-        buf.write_str(" ").unwrap();
     }
+    let mut text = String::new();
+    config.text_encoding
+        .write(&mut text, offset, chunk, config.placeholder, redacted,
+               |w, _, c| w.write_char(c.unwrap_or(' ')))
+        .unwrap();
+    (position, data, text)
+}
 
-    for &b in chunk {
-        write!(buf, "{}", sanitize_byte(b)).unwrap();
+/// Formats a size like `2623 bytes, 2.6 KiB`, omitting the binary unit
+/// below 1 KiB.
+fn write_size<W: fmt::Write>(buf: &mut W, len: usize) {
+    const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+    write!(buf, "{} bytes", len).unwrap();
+    if len < 1024 {
+        return;
+    }
+    let mut size = len as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
     }
+    write!(buf, ", {:.1} {}", size, UNITS[unit]).unwrap();
+}
 
-    for _ in chunk.len()..CHUNK_LENGTH {
+/// Formats the summary line showing the offset after the last byte.
+///
+/// `len` is the number of bytes dumped, shown if `Config::summary_size` is
+/// set.
+pub(crate) fn hexdump_summary(config: &Config, offset: usize, len: usize) -> Line {
+    if config.frame {
+        let mut tail = String::new();
+        write_offset(&mut tail, config, offset);
+        let mut size = String::new();
+        if config.summary_size {
+            write_size(&mut size, len);
+        }
+        return if config.columns.has_data() {
+            frame::row(config, &size, "", &tail)
+        } else {
+            frame::row(config, "", &size, &tail)
+        };
+    }
+    let mut buf = Buffer::new();
+    for _ in 0..line_width(config, config.chunk_length()) - offset_width(config) {
         buf.write_str(" ").unwrap();
     }
+    write_offset(&mut buf, config, offset);
+    if config.summary_size {
+        buf.write_str("  (").unwrap();
+        write_size(&mut buf, len);
+        buf.write_str(")").unwrap();
+    }
+
+    Line::new(buf)
+}
+
+/// Formats a data line showing `chunk` at `offset`.
+pub(crate) fn format_line(config: &Config, offset: usize, chunk: &[u8]) -> Line {
+    hexdump_chunk(config, config.base_offset + offset, 0, offset, chunk, 0..chunk.len())
+}
 
-    buf.write_str(" ").unwrap();
-    write!(buf, "{:08x}", offset).unwrap();
+/// Writes the line formatted by `format_line` to `buf`.
+pub(crate) fn write_line<W: fmt::Write>(buf: &mut W, config: &Config, offset: usize, chunk: &[u8])
+    -> fmt::Result
+{
+    write_chunk(buf, config, config.base_offset + offset, 0, offset, chunk, 0..chunk.len())
+}
+
+/// Writer filling a byte slice, failing once it is full.
+pub(crate) struct SliceWriter<'a> {
+    pub(crate) buf: &'a mut [u8],
+    pub(crate) len: usize,
+}
+
+impl<'a> fmt::Write for SliceWriter<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        if end > self.buf.len() {
+            return Err(fmt::Error);
+        }
+        self.buf[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
 
+/// Formats a line starting at `offset` whose first `lead` positions are
+/// left blank.
+///
+/// The line shows `context[range]`, the surrounding bytes are only used for
+/// highlight rules.
+fn hexdump_chunk(config: &Config, offset: usize, lead: usize, position: usize, context: &[u8],
+                 range: ops::Range<usize>)
+    -> Line
+{
+    let mut buf = Buffer::new();
+    write_chunk(&mut buf, config, offset, lead, position, context, range).unwrap();
     Line::new(buf)
 }
 
+/// Returns whether data lines use none of the options handled by
+/// `write_chunk`, so full chunks can be written by `write_plain_chunk`.
+fn is_plain(config: &Config) -> bool {
+    config.byte_format == ByteFormat::Hex
+        && config.chunk_length() == CHUNK_LENGTH
+        && config.text_encoding == TextEncoding::Ascii
+        && config.placeholder == '.'
+        && !config.group_text
+        && !(config.has_styles() && config.colors_enabled())
+        && config.columns == Columns::DataText
+        && config.delimiters.0 == "|"
+        && config.delimiters.1 == "|"
+        && config.segment_separator == " "
+        && !config.frame
+        && config.redactions.is_empty()
+        && !config.entropy
+        && config.checksum.is_none()
+        && config.symbols.is_none()
+}
+
+/// Writes the line of a full chunk like `write_chunk`, for configurations
+/// accepted by `is_plain`.
+///
+/// Fills a fixed-size array instead of going through the formatting
+/// machinery byte by byte, which matters for large dumps.
+fn write_plain_chunk<W: fmt::Write>(buf: &mut W, config: &Config, offset: usize, chunk: &[u8])
+    -> fmt::Result
+{
+    const DATA_LENGTH: usize = CHUNK_LENGTH * 2 + CHUNK_LENGTH / SEGMENT_LENGTH - 1;
+    let digits: &[u8; 16] = if config.uppercase {
+        b"0123456789ABCDEF"
+    } else {
+        b"0123456789abcdef"
+    };
+    let mut line = [b' '; 1 + DATA_LENGTH + 2 + CHUNK_LENGTH + 1];
+    let (data, text) = line.split_at_mut(1 + DATA_LENGTH + 2);
+    data[0] = b'|';
+    data[DATA_LENGTH + 1] = b'|';
+    for (i, &b) in chunk.iter().enumerate() {
+        let cell = 1 + i * 2 + i / SEGMENT_LENGTH;
+        data[cell] = digits[(b >> 4) as usize];
+        data[cell + 1] = digits[(b & 0xf) as usize];
+        text[i] = sanitize_byte(b) as u8;
+    }
+    // The array only ever contains ASCII characters.
+    buf.write_str(str::from_utf8(&line).unwrap())?;
+    let mut tail: Buffer = Buffer::new();
+    write_offset(&mut tail, config, offset);
+    buf.write_str(&tail)
+}
+
+/// Writes the line formatted by `hexdump_chunk` to `buf`.
+fn write_chunk<W: fmt::Write>(buf: &mut W, config: &Config, offset: usize, lead: usize,
+                              position: usize, context: &[u8], range: ops::Range<usize>)
+    -> fmt::Result
+{
+    let chunk = &context[range.clone()];
+    let chunk_length = config.chunk_length();
+    let format = config.byte_format;
+    let redacted = |i: usize| config.is_redacted(position + i);
+    let colors = config.has_styles() && config.colors_enabled();
+    let style = |i: usize| {
+        if !colors || redacted(i) {
+            return None;
+        }
+        let style = match config.style {
+            Some(ref style) => (style.0)(position + i, chunk[i]),
+            None => Style::new(),
+        };
+        if !style.is_plain() {
+            return Some(style);
+        }
+        config.highlights.iter()
+            .find(|&&(rule, _)| {
+                rule.matches(context, range.start + i, config.base_offset + position + i)
+            })
+            .map(|&(_, style)| style)
+    };
+    let data_width = data_width(config, chunk_length);
+    let lead_width = cell_column(config, lead);
+    let write_data = |buf: &mut W| -> fmt::Result {
+        for _ in 0..lead_width {
+            buf.write_str(" ")?;
+        }
+        if !config.frame {
+            buf.write_str(&config.delimiters.0)?;
+        }
+        let mut width = lead_width;
+        for (i, &b) in chunk.iter().enumerate() {
+            if i != 0 {
+                for _ in 0..format.separator() {
+                    buf.write_str(" ")?;
+                }
+                width += format.separator();
+                if (lead + i).is_multiple_of(SEGMENT_LENGTH) {
+                    buf.write_str(&config.segment_separator)?;
+                    width += segment_separator_width(config);
+                }
+            }
+            if redacted(i) {
+                for _ in 0..format.width() {
+                    buf.write_str("x")?;
+                }
+            } else if let Some(style) = style(i) {
+                style.write_start(buf)?;
+                format.write(buf, b, config.uppercase)?;
+                style.write_end(buf)?;
+            } else if colors && config.heatmap {
+                let (red, green, blue) = heatmap_color(b);
+                write!(buf, "\x1b[38;2;{};{};{}m", red, green, blue)?;
+                format.write(buf, b, config.uppercase)?;
+                buf.write_str("\x1b[0m")?;
+            } else {
+                format.write(buf, b, config.uppercase)?;
+            }
+            width += format.width();
+        }
+        if !config.frame {
+            buf.write_str(&config.delimiters.1)?;
+        }
+        for _ in width..data_width {
+            buf.write_str(" ")?;
+        }
+        Ok(())
+    };
+    let write_text = |buf: &mut W| -> fmt::Result {
+        let mut width = text_column(config, lead);
+        for _ in 0..width {
+            buf.write_str(" ")?;
+        }
+        config.text_encoding.write(buf, position, chunk, config.placeholder, redacted, |w, i, c| {
+            if i != 0 && config.group_text && (lead + i).is_multiple_of(SEGMENT_LENGTH) {
+                w.write_str(" ")?;
+                width += 1;
+            }
+            width += 1;
+            match (c, style(i)) {
+                (None, _) => w.write_str(" "),
+                (Some(c), Some(style)) => {
+                    style.write_start(w)?;
+                    w.write_char(c)?;
+                    style.write_end(w)
+                }
+                (Some(c), None) => w.write_char(c),
+            }
+        })?;
+        for _ in width..text_width(config, chunk_length) {
+            buf.write_str(" ")?;
+        }
+        Ok(())
+    };
+
+    let separator = if config.frame { frame::MIDDLE } else { " " };
+    if config.frame {
+        buf.write_str(frame::LEFT)?;
+    }
+    for &column in config.columns.order() {
+        match column {
+            Column::Data => write_data(buf)?,
+            Column::Text => write_text(buf)?,
+        }
+        buf.write_str(separator)?;
+    }
+    let mut tail: Buffer = Buffer::new();
+    write_offset(&mut tail, config, offset);
+
+    // Don't leak information about redacted bytes through the extra columns.
+    let any_redacted = (0..chunk.len()).any(redacted);
+    if config.entropy {
+        if any_redacted {
+            tail.write_str(" x.xx")?;
+        } else {
+            write!(tail, " {:.2}", entropy(chunk))?;
+        }
+    }
+    if let Some(checksum) = config.checksum {
+        if any_redacted {
+            write!(tail, " {:x<1$}", "", checksum.width())?;
+        } else {
+            write!(tail, " {:01$x}", checksum.compute(chunk), checksum.width())?;
+        }
+    }
+    buf.write_str(&tail)?;
+    if config.frame {
+        for _ in tail.chars().count()..frame::tail_width(config) {
+            buf.write_str(" ")?;
+        }
+        buf.write_str(frame::RIGHT)?;
+    }
+    if let Some(ref symbols) = config.symbols {
+        if let Some(symbol) = (symbols.0)(offset) {
+            write!(buf, " <{}>", symbol)?;
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use super::CHUNK_LENGTH;
+    use super::Config;
+    use super::Line;
+    use super::entropy;
+    use super::line_width;
+    use config::Checksum;
+    use config::LineTerminator;
+    use super::hexdump_chunk;
     use super::hexdump_iter;
+    use super::hexdump_lines;
+    use super::hexdump_range_iter;
+    use super::hexdump_string;
+    use super::hexdump_tail_iter;
+    use super::hexdump_write;
     use super::sanitize_byte;
+    use testutil::Closed;
 
+    use std::cmp;
     use std::collections::HashSet;
     use std::convert::TryFrom;
+    use std::io;
+    use std::mem;
 
     quickcheck! {
+        fn plain_chunks(bytes: Vec<u8>, base_offset: u16, uppercase: bool) -> bool {
+            let config = Config::new().base_offset(base_offset as usize).uppercase(uppercase);
+            let lines: Vec<_> = config.hexdump_iter(&bytes).collect();
+            bytes.chunks(CHUNK_LENGTH).enumerate().all(|(i, chunk)| {
+                let start = i * CHUNK_LENGTH;
+                let expected = hexdump_chunk(&config, base_offset as usize + start, 0, start,
+                                             &bytes, start..start + chunk.len());
+                *lines[i] == *expected
+            })
+        }
+        fn align_base_offset(bytes: Vec<u8>, base_offset: usize) -> bool {
+            let base_offset = base_offset % 0x10000;
+            let lines: Vec<_> = Config::new().base_offset(base_offset).align_base_offset(true)
+                .hexdump_iter(&bytes).collect();
+            let (summary, data) = lines.split_last().unwrap();
+            let offsets: Vec<_> = data.iter().map(|l| l.offset().unwrap()).collect();
+            let parsed: Vec<u8> = data.iter().flat_map(|l| l.data().unwrap()).collect();
+            offsets.iter().all(|&o| o % CHUNK_LENGTH == 0)
+                && offsets.first().is_none_or(|&o| o == base_offset / CHUNK_LENGTH * CHUNK_LENGTH)
+                && summary.offset() == Some(base_offset + bytes.len())
+                && parsed == bytes
+        }
+        fn for_each_line(bytes: Vec<u8>, skip: usize) -> bool {
+            let config = Config::new().title("t").annotate(1..3, "x").max_lines(3);
+            let mut iter = config.hexdump_iter(&bytes);
+            iter.nth(skip % 4);
+            let mut lines = Vec::new();
+            iter.clone().for_each_line(|l| lines.push(l.to_owned()));
+            lines == iter.map(|l| l.to_string()).collect::<Vec<_>>()
+        }
+        fn write_line_into(bytes: Vec<u8>) -> bool {
+            let config = Config::new();
+            let mut buf = [0; 128];
+            bytes.chunks(CHUNK_LENGTH).zip(hexdump_iter(&bytes)).enumerate().all(|(i, (c, l))| {
+                let len = config.write_line_into(i * CHUNK_LENGTH, c, &mut buf);
+                buf[..len] == *l.as_bytes()
+            })
+        }
         fn length(bytes: Vec<u8>) -> bool {
             let len = hexdump_iter(b"").next().unwrap().len();
             hexdump_iter(&bytes).all(|s| s.len() == len)
         }
-
         fn ascii_only_no_cc(bytes: Vec<u8>) -> bool {
-            hexdump_iter(&bytes).all(|s| s.bytes().all(|b| 0x20 <= b && b < 0x7f))
+            hexdump_iter(&bytes).all(|s| s.bytes().all(|b| (0x20..0x7f).contains(&b)))
         }
-
         fn summary(bytes: Vec<u8>) -> bool {
             usize::from_str_radix(hexdump_iter(&bytes).last().unwrap().trim(), 16).ok()
                 == Some(bytes.len())
         }
-
+        fn fused(bytes: Vec<u8>) -> bool {
+            let mut iter = hexdump_iter(&bytes);
+            iter.by_ref().for_each(drop);
+            iter.next().is_none() && iter.next_back().is_none() && iter.next().is_none()
+        }
         fn chars_existent(bytes: Vec<u8>) -> bool {
             let printable_chars: HashSet<_> = bytes.iter()
-                .filter(|&&b| 0x20 <= b && b < 0x7f)
+                .filter(|&&b| (0x20..0x7f).contains(&b))
                 .map(|&b| b as char)
                 .collect();
             let lines: Vec<_> = hexdump_iter(&bytes).map(|l| l.to_owned()).collect();
@@ -225,12 +1819,427 @@ mod test {
 
             printable_chars.is_subset(&printed_chars)
         }
-
+        fn line_at(bytes: Vec<u8>, skip: u8) -> bool {
+            let mut iter = hexdump_iter(&bytes);
+            let skip = skip as usize % (iter.len() + 1);
+            if skip != 0 {
+                iter.nth(skip - 1);
+            }
+            let lines: Vec<_> = iter.clone().map(|l| l.to_string()).collect();
+            let rev: Vec<_> = lines.iter().rev().cloned().collect();
+            (0..lines.len() + 1).all(|i| {
+                let expected = lines.get(i).cloned();
+                iter.line_at(i).map(|l| l.to_string()) == expected
+                    && iter.clone().nth(i).map(|l| l.to_string()) == expected
+                    && iter.clone().nth_back(i).map(|l| l.to_string()) == rev.get(i).cloned()
+            })
+        }
+        fn title(bytes: Vec<u8>) -> bool {
+            let config = Config::new().title("packet");
+            let mut iter = config.hexdump_iter(&bytes);
+            iter.len() == hexdump_iter(&bytes).len() + 1
+                && *iter.next().unwrap() == format!("---- packet ({} bytes) ----", bytes.len())
+                && iter.map(|l| l.to_string()).eq(hexdump_iter(&bytes).map(|l| l.to_string()))
+        }
+        fn write(bytes: Vec<u8>) -> bool {
+            let mut output = Vec::new();
+            hexdump_write(&mut output, &bytes).unwrap();
+            let expected: String = hexdump_iter(&bytes).map(|l| format!("{}\n", l)).collect();
+            output == expected.into_bytes()
+        }
+        fn parse_roundtrip(bytes: Vec<u8>, start: usize) -> bool {
+            let start = if bytes.is_empty() { 0 } else { start % bytes.len() };
+            let mut parsed = Vec::new();
+            let mut offsets = Vec::new();
+            for line in hexdump_range_iter(&bytes, start..bytes.len()) {
+                let line: Line = line.parse().unwrap();
+                offsets.push(line.offset().unwrap());
+                parsed.extend(line.data().unwrap_or_default());
+            }
+            parsed == bytes[start..]
+                && offsets.last() == Some(&bytes.len())
+                && offsets[..offsets.len() - 1].iter().enumerate()
+                    .all(|(i, &o)| o == (start / CHUNK_LENGTH + i) * CHUNK_LENGTH)
+        }
         fn line_count(bytes: Vec<u8>) -> bool {
-            let expected = (bytes.len() + CHUNK_LENGTH - 1) / CHUNK_LENGTH + 1;
+            let expected = bytes.len().div_ceil(CHUNK_LENGTH) + 1;
             hexdump_iter(&bytes).len() == expected
                 && hexdump_iter(&bytes).count() == expected
         }
+        fn max_lines(bytes: Vec<u8>, max: u8) -> bool {
+            let max = max as usize % 4;
+            let num_chunks = bytes.len().div_ceil(CHUNK_LENGTH);
+            let lines: Vec<_> = Config::new().max_lines(max).hexdump_iter(&bytes)
+                .map(|l| l.to_string()).collect();
+            if num_chunks <= max {
+                return lines.len() == num_chunks + 1;
+            }
+            let omitted = format!("... {} bytes omitted ...", bytes.len() - max * CHUNK_LENGTH);
+            lines.len() == max + 2
+                && lines[max] == omitted
+                && lines[max + 1] == hexdump_iter(&bytes).next_back().unwrap().to_string()
+        }
+        fn head_tail(bytes: Vec<u8>, head: u8, tail: u8) -> bool {
+            let (head, tail) = (head as usize % 4, tail as usize % 4);
+            let all: Vec<_> = hexdump_iter(&bytes).map(|l| l.to_string()).collect();
+            let lines: Vec<_> = Config::new().head_tail(head, tail).hexdump_iter(&bytes)
+                .map(|l| l.to_string()).collect();
+            let num_chunks = all.len() - 1;
+            if num_chunks <= head + tail {
+                return lines == all;
+            }
+            let end = cmp::min(bytes.len(), (num_chunks - tail) * CHUNK_LENGTH);
+            let omitted = end - head * CHUNK_LENGTH;
+            lines.len() == head + tail + 2
+                && lines[..head] == all[..head]
+                && lines[head] == format!("... {} bytes omitted ...", omitted)
+                && lines[head + 1..] == all[num_chunks - tail..]
+        }
+        fn bytes_per_line(bytes: Vec<u8>, segments: u8) -> bool {
+            let bytes_per_line = (segments as usize % 16 + 1) * 4;
+            let config = Config::new().bytes_per_line(bytes_per_line);
+            let mut lines: Vec<_> = config.hexdump_iter(&bytes).collect();
+            let summary = lines.pop().unwrap();
+            lines.len() == bytes.len().div_ceil(bytes_per_line)
+                && lines.iter().all(|l| l.len() == line_width(&config, bytes_per_line))
+                && summary.len() == line_width(&config, bytes_per_line)
+                && lines.iter().enumerate().all(|(i, l)| {
+                    l.ends_with(&format!(" {:08x}", i * bytes_per_line))
+                })
+        }
+        fn range(bytes: Vec<u8>, a: usize, b: usize) -> bool {
+            let (a, b) = if bytes.is_empty() {
+                (0, 0)
+            } else {
+                (a % bytes.len(), b % bytes.len())
+            };
+            let (start, end) = (cmp::min(a, b), cmp::max(a, b));
+            let lines: Vec<_> = hexdump_range_iter(&bytes, start..end)
+                .map(|l| l.to_string()).collect();
+            let all: Vec<_> = hexdump_iter(&bytes).map(|l| l.to_string()).collect();
+            let first = start / CHUNK_LENGTH;
+            let last = if start == end { first } else { end.div_ceil(CHUNK_LENGTH) };
+            lines.len() == last - first + 1
+                && lines.iter().all(|l| l.len() == all[0].len())
+                && lines[..lines.len() - 1].iter().zip(&all[first..last])
+                    .all(|(l, a)| l.ends_with(&a[a.len() - 8..]))
+                && usize::from_str_radix(lines.last().unwrap().trim(), 16).ok() == Some(end)
+        }
+    }
+
+    #[test]
+    fn test_line_traits() {
+        let line = hexdump_iter(b"").next().unwrap();
+        let mut set = HashSet::new();
+        set.insert(line.clone());
+        assert!(set.contains(&*line));
+        assert_eq!(line, hexdump_iter(b"").next().unwrap());
+        assert!(line != hexdump_iter(b"a").next().unwrap());
+        assert_eq!(line, *line.as_ref());
+        assert_eq!(line.trim(), "00000000");
+        assert_eq!(&*line, line);
+    }
+
+    #[test]
+    fn test_parse_line() {
+        assert!("".parse::<Line>().is_err());
+        assert!("|6x| f 00000000".parse::<Line>().is_err());
+        assert!("|66| g 00000000".parse::<Line>().is_err());
+        assert!("|66| f 0000".parse::<Line>().is_err());
+        assert!("---- title (0 bytes) ----".parse::<Line>().is_err());
+        let line: Line = "|6667| fg 00000010".parse().unwrap();
+        assert_eq!(line.offset(), Some(0x10));
+        assert_eq!(line.data(), Some(b"fg".to_vec()));
+        let summary: Line = "   00000012".parse().unwrap();
+        assert_eq!(summary.offset(), Some(0x12));
+        assert_eq!(summary.data(), None);
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(hexdump_string(b"12345\0\r\n\t .abcdef"), "\
+|31323334 35000d0a 09202e61 62636465| 12345.... .abcde 00000000
+|66|                                  f                00000010
+                                                       00000011
+");
+        assert_eq!(hexdump_string(b""), format!("{:>63}\n", "00000000"));
+        assert_eq!(hexdump_lines(b"12345\0\r\n\t .abcdef").join("\n") + "\n",
+                   hexdump_string(b"12345\0\r\n\t .abcdef"));
+    }
+
+    #[test]
+    fn test_normalize_offsets() {
+        let bytes: Vec<u8> = (0..0x40).collect();
+        let config = Config::new().normalize_offsets(true);
+        assert_eq!(config.hexdump_range_string(&bytes, 0x22..0x30),
+                   Config::new().hexdump_range_string(&bytes[0x20..0x30], 2..0x10));
+    }
+
+    #[test]
+    fn test_range_padding() {
+        let bytes: Vec<u8> = (b'a'..=b'z').collect();
+        let lines: Vec<_> = hexdump_range_iter(&bytes, 5..18).map(|l| l.to_string()).collect();
+        assert_eq!(lines[0], "           |666768 696a6b6c 6d6e6f70|      fghijklmnop 00000000");
+        assert_eq!(lines[1], "|7172|                                qr               00000010");
+    }
+
+    #[test]
+    fn test_entropy() {
+        assert_eq!(entropy(b""), 0.0);
+        assert_eq!(entropy(b"aaaa"), 0.0);
+        assert_eq!(entropy(b"abab"), 1.0);
+        assert_eq!(entropy(&(0..=255).collect::<Vec<u8>>()), 8.0);
+        let lines: Vec<_> = Config::new().entropy(true).hexdump_iter(b"0123456789abcdefaaaa")
+            .map(|l| l.to_string()).collect();
+        assert!(lines[0].ends_with(" 00000000 4.00"));
+        assert!(lines[1].ends_with(" 00000010 0.00"));
+        assert!(lines[2].ends_with(" 00000014"));
+    }
+
+    #[test]
+    fn test_checksum() {
+        assert_eq!(Checksum::Crc32.compute(b""), 0);
+        assert_eq!(Checksum::Crc32.compute(b"123456789"), 0xcbf4_3926);
+        assert_eq!(Checksum::Sum8.compute(&[0xff, 0x02]), 0x01);
+        assert_eq!(Checksum::Xor8.compute(&[0xff, 0x0f]), 0xf0);
+        let mut iter = Config::new().checksum(Checksum::Crc32).hexdump_iter(b"123456789");
+        assert!(iter.next().unwrap().ends_with(" 00000000 cbf43926"));
+        let mut iter = Config::new().checksum(Checksum::Sum8).hexdump_iter(&[0x01, 0x02]);
+        assert!(iter.next().unwrap().ends_with(" 00000000 03"));
+    }
+
+    #[test]
+    fn test_annotate() {
+        let bytes: Vec<u8> = (0..0x20).collect();
+        let config = Config::new()
+            .annotate(0x00..0x04, "magic")
+            .annotate(0x0e..0x12, "length")
+            .inspect(0x02);
+        let lines: Vec<_> = config.hexdump_iter(&bytes).map(|l| l.to_string()).collect();
+        assert_eq!(lines[1], " ^^^^^^^^ magic");
+        assert_eq!(lines[2], "                                ^^^^ length");
+        assert_eq!(lines[3], "     ^^ 00000002");
+        assert!(lines[4].starts_with("    u8  2"));
+        assert_eq!(lines[11], " ^^^^ length (cont.)");
+        assert_eq!(lines.len(), 3 + 9 + 1);
+    }
+
+    #[test]
+    fn test_redact() {
+        let config = Config::new()
+            .redact(3..6)
+            .redact(17..18)
+            .checksum(Checksum::Sum8)
+            .inspect(2);
+        let lines: Vec<_> = config.hexdump_iter(b"user=secret&x=1\n!?")
+            .map(|l| l.to_string()).collect();
+        assert_eq!(lines[0], "|757365xx xxxx6563 72657426 783d310a| use***ecret&x=1. 00000000 xx");
+        assert_eq!(lines[2], "    u8  101");
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[3], "|21xx|                                !*               00000010 xx");
+    }
+
+    #[test]
+    fn test_base_offset() {
+        let config = Config::new().base_offset(0x1f3);
+        let lines: Vec<_> = config.hexdump_iter(b"0123456789abcdefXY").map(|l| l.to_string()).collect();
+        assert_eq!(lines, [
+            "|30313233 34353637 38396162 63646566| 0123456789abcdef 000001f3",
+            "|5859|                                XY               00000203",
+            "                                                       00000205",
+        ]);
+        let mut continuation = config.continuation();
+        let first: Vec<_> = continuation.hexdump_iter(b"0123456789abcdef")
+            .map(|l| l.to_string()).collect();
+        let second: Vec<_> = continuation.hexdump_iter(b"XY").map(|l| l.to_string()).collect();
+        assert_eq!(first[0], lines[0]);
+        assert_eq!(second, lines[1..]);
+        assert_eq!(continuation.offset(), 0x205);
+    }
+
+    #[test]
+    fn test_relative_to() {
+        let bytes: Vec<u8> = (0..40).collect();
+        let lines: Vec<_> = Config::new().relative_to(0x10).bytes_per_line(8)
+            .hexdump_range_iter(&bytes, 4..32).map(|l| l.to_string()).collect();
+        assert_eq!(lines, [
+            "         |04050607|     .... -00000010",
+            "|08090a0b 0c0d0e0f| ........ -00000008",
+            "|10111213 14151617| ........ +00000000",
+            "|18191a1b 1c1d1e1f| ........ +00000008",
+            "                             +00000010",
+        ]);
+    }
+
+    #[test]
+    fn test_delimiters() {
+        let config = Config::new().delimiters("", " ").segment_separator(" : ")
+            .annotate(5..7, "x");
+        let lines: Vec<_> = config.hexdump_range_iter(b"0123456789abcdefXY", 1..18)
+            .map(|l| l.to_string()).collect();
+        assert_eq!(lines, [
+            "  313233 : 34353637 : 38396162 : 63646566   123456789abcdef 00000000",
+            "             ^^^^ x",
+            "5859                                       XY               00000010",
+            "                                                            00000012",
+        ]);
+        assert_eq!(lines[0].len(), line_width(&config, 16));
+    }
+
+    #[test]
+    fn test_summary_size() {
+        let config = Config::new().summary_size(true);
+        let summary = config.hexdump_iter(&[0; 2623]).last().unwrap();
+        assert!(summary.ends_with(" 00000a3f  (2623 bytes, 2.6 KiB)"));
+        assert_eq!(summary.offset(), Some(2623));
+        let summary = config.hexdump_iter(b"ab").last().unwrap();
+        assert!(summary.ends_with(" 00000002  (2 bytes)"));
+        assert_eq!(summary.parse::<Line>().unwrap().offset(), Some(2));
+        let summary = config.base_offset(0x100).hexdump_iter(&[0; 3 << 20]).last().unwrap();
+        assert!(summary.ends_with(" 00300100  (3145728 bytes, 3.0 MiB)"));
+    }
+
+    #[test]
+    fn test_base_offset_end() {
+        let config = Config::new().base_offset(usize::MAX - 4);
+        let summary = config.hexdump_iter(b"abcd").last().unwrap();
+        assert_eq!(summary.offset(), Some(usize::MAX));
+        let mut continuation = config.continuation();
+        continuation.hexdump_iter(b"abcd");
+        assert_eq!(continuation.offset(), usize::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows usize")]
+    fn test_base_offset_overflow() {
+        let mut continuation = Config::new().base_offset(usize::MAX - 4).continuation();
+        continuation.hexdump_iter(b"abcd");
+        continuation.hexdump_iter(b"e");
+    }
+
+    #[test]
+    fn test_symbols() {
+        let config = Config::new().bytes_per_line(4).base_offset(0x1000)
+            .symbols(|offset| if offset < 0x1004 { Some(".text".into()) } else { None });
+        let lines: Vec<_> = config.hexdump_iter(b"abcdef").map(|l| l.to_string()).collect();
+        assert_eq!(lines, [
+            "|61626364| abcd 00001000 <.text>",
+            "|6566|     ef   00001004",
+            "                00001006",
+        ]);
+    }
+
+    #[test]
+    fn test_collapse_runs() {
+        let mut bytes = vec![0; 64];
+        bytes[0] = b'a';
+        bytes.extend(b"bcde");
+        bytes.extend(&[0xff; 8]);
+        let config = Config::new().bytes_per_line(4).collapse_runs(8).annotate(70..72, "ff");
+        let lines: Vec<_> = config.hexdump_iter(&bytes).map(|l| l.to_string()).collect();
+        assert_eq!(lines, [
+            "|61000000| a... 00000000",
+            "** 0x00 x 60 bytes (0x4..0x40) **",
+            "|62636465| bcde 00000040",
+            "** 0xff x 8 bytes (0x44..0x4c) **",
+            "                0000004c",
+        ]);
+        let lines: Vec<_> = config.max_lines(2).hexdump_iter(&bytes).map(|l| l.to_string())
+            .collect();
+        assert_eq!(lines[2], "... 12 bytes omitted ...");
+        // Lines with redacted bytes stay visible.
+        let config = Config::new().bytes_per_line(4).collapse_runs(16);
+        assert_eq!(config.hexdump_iter(&[0; 16]).count(), 2);
+        assert_eq!(config.redact(6..7).hexdump_iter(&[0; 16]).count(), 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "buffer too small")]
+    fn test_write_line_into_small_buffer() {
+        Config::new().write_line_into(0, b"abc", &mut [0; 16]);
+    }
+
+    #[test]
+    fn test_inline_capacity() {
+        let config = Config::new().bytes_per_line(32);
+        let line = config.hexdump_iter(&[0; 32]).next().unwrap();
+        assert!(line.inner.is_spilled());
+        assert_eq!(*line.clone(), *line);
+        let wide = config.hexdump_iter(&[0; 32]).with_inline_capacity::<128>().next().unwrap();
+        assert!(!wide.inner.is_spilled());
+        assert_eq!(*wide, *line);
+        assert_eq!(mem::size_of::<Line>(), 65);
+    }
+
+    #[test]
+    fn test_tail() {
+        let bytes: Vec<u8> = (0..100).collect();
+        let expected = hexdump_range_iter(&bytes, 70..100).collect::<Vec<_>>();
+        assert_eq!(hexdump_tail_iter(&bytes, 30).collect::<Vec<_>>(), expected);
+        assert_eq!(hexdump_tail_iter(&bytes, 1000).count(), hexdump_iter(&bytes).count());
+        let mut output = Vec::new();
+        Config::new().write_tail(&mut output, io::Cursor::new(&bytes), 30).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), Config::new().hexdump_range_string(&bytes, 70..100));
+        let error = Config::new().base_offset(usize::MAX - 50)
+            .write_tail(io::sink(), io::Cursor::new(&bytes), 30).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_broken_pipe() {
+        let error = hexdump_write(Closed, b"abc").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::BrokenPipe);
+    }
+
+    #[test]
+    fn test_owned() {
+        use std::thread;
+        let bytes: Vec<u8> = (0..40).collect();
+        let expected: Vec<_> = Config::new().title("x").hexdump_iter(&bytes).collect();
+        let owned = Config::new().title("x").hexdump_owned_iter(bytes.clone());
+        let lines: Vec<_> = thread::spawn(move || owned.collect()).join().unwrap();
+        assert_eq!(lines, expected);
+        let mut iter = Config::new().title("x").hexdump_iter(&bytes);
+        iter.next();
+        let owned = iter.into_owned();
+        drop(bytes);
+        assert_eq!(owned.collect::<Vec<_>>(), expected[1..]);
+    }
+
+    #[test]
+    fn test_split_at() {
+        let bytes: Vec<u8> = (0..100).collect();
+        let config = Config::new().title("x").collapse_runs(16).max_lines(4);
+        let expected: Vec<_> = config.hexdump_iter(&bytes).collect();
+        for index in 0..expected.len() + 1 {
+            let (first, second) = config.hexdump_iter(&bytes).split_at(index);
+            assert_eq!(first.len(), index);
+            assert_eq!(first.collect::<Vec<_>>(), expected[..index]);
+            assert_eq!(second.rev().collect::<Vec<_>>(),
+                       expected[index..].iter().rev().cloned().collect::<Vec<_>>());
+        }
+        let mut iter = config.hexdump_iter(&bytes);
+        iter.next();
+        let (first, _) = iter.split_at(1);
+        assert_eq!(first.collect::<Vec<_>>(), expected[1..2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "split index")]
+    fn test_split_at_out_of_bounds() {
+        let iter = hexdump_iter(b"abc");
+        let len = iter.len();
+        iter.split_at(len + 1);
+    }
+
+    #[test]
+    fn test_line_terminator() {
+        let config = Config::new().line_terminator(LineTerminator::CrLf).trailing_newline(false);
+        let mut output = Vec::new();
+        config.write(&mut output, b"abc").unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches("\r\n").count(), 1);
+        assert!(!output.ends_with('\n'));
     }
 
     #[test]
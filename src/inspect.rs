@@ -0,0 +1,64 @@
+use imp::Buffer;
+use imp::Line;
+use std::fmt::Write;
+
+macro_rules! inspect_value {
+    ($lines:ident, $bytes:ident, $name:expr, $ty:ty, $len:expr) => {
+        if $bytes.len() >= $len {
+            let mut array = [0; $len];
+            array.copy_from_slice(&$bytes[..$len]);
+            let mut buf = Buffer::new();
+            write!(buf, "    {:<3} le {:?} be {:?}", $name,
+                   <$ty>::from_le_bytes(array), <$ty>::from_be_bytes(array)).unwrap();
+            $lines.push(Line::new(buf));
+        }
+    }
+}
+
+/// Formats the data inspector lines for the given bytes, starting with a
+/// line that marks the hex representation of the first byte at `column`.
+pub(crate) fn inspect(bytes: &[u8], offset: usize, column: usize) -> Vec<Line> {
+    let mut lines = Vec::new();
+
+    let mut buf = Buffer::new();
+    write!(buf, "{:1$}^^ {2:08x}", "", column, offset).unwrap();
+    lines.push(Line::new(buf));
+
+    if let Some(&b) = bytes.first() {
+        let mut buf = Buffer::new();
+        write!(buf, "    u8  {}", b).unwrap();
+        lines.push(Line::new(buf));
+    }
+    inspect_value!(lines, bytes, "u16", u16, 2);
+    inspect_value!(lines, bytes, "u32", u32, 4);
+    inspect_value!(lines, bytes, "u64", u64, 8);
+    inspect_value!(lines, bytes, "f32", f32, 4);
+    inspect_value!(lines, bytes, "f64", f64, 8);
+    lines
+}
+
+#[cfg(test)]
+mod test {
+    use config::Config;
+
+    #[test]
+    fn test_inspect() {
+        let bytes: Vec<u8> = (0..0x40).collect();
+        let config = Config::new().inspect(0x13).inspect(0x3e).head_tail(1, 1);
+        let lines: Vec<_> = config.hexdump_iter(&bytes).map(|l| l.to_string()).collect();
+        assert_eq!(lines.len(), 4 + 3);
+        assert!(lines[2].ends_with(" 00000030"));
+        assert_eq!(lines[3], "                                ^^ 0000003e");
+        assert_eq!(lines[4], "    u8  62");
+        assert_eq!(lines[5], "    u16 le 16190 be 15935");
+        let iter = config.hexdump_iter(&bytes);
+        assert!((0..lines.len()).all(|i| iter.line_at(i).unwrap() == *lines[i]));
+
+        let lines: Vec<_> = Config::new().inspect(0x08).hexdump_iter(&bytes)
+            .map(|l| l.to_string()).collect();
+        assert_eq!(lines.len(), 5 + 7);
+        assert_eq!(lines[1], "                   ^^ 00000008");
+        assert_eq!(lines[5], "    u64 le 1084818905618843912 be 579005069656919567");
+        assert_eq!(lines[6], "    f32 le 2.6584628e-32 be 4.1238743e-34");
+    }
+}
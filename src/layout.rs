@@ -0,0 +1,185 @@
+use std::fmt::Write;
+use std::ops;
+
+/// Byte order of a multi-byte field.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Endian {
+    /// Least significant byte first.
+    Little,
+    /// Most significant byte first.
+    Big,
+}
+
+/// Type of a field in a `Layout`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FieldType {
+    /// Unsigned 8-bit integer.
+    U8,
+    /// Signed 8-bit integer.
+    I8,
+    /// Unsigned 16-bit integer.
+    U16(Endian),
+    /// Signed 16-bit integer.
+    I16(Endian),
+    /// Unsigned 32-bit integer.
+    U32(Endian),
+    /// Signed 32-bit integer.
+    I32(Endian),
+    /// Unsigned 64-bit integer.
+    U64(Endian),
+    /// Signed 64-bit integer.
+    I64(Endian),
+    /// 32-bit floating point number.
+    F32(Endian),
+    /// 64-bit floating point number.
+    F64(Endian),
+    /// Opaque bytes of the given length, shown in hex.
+    Bytes(usize),
+}
+
+macro_rules! decode {
+    ($bytes:ident, $ty:ty, $len:expr, $endian:expr) => {{
+        let mut array = [0; $len];
+        array.copy_from_slice($bytes);
+        match $endian {
+            Endian::Little => format!("{:?}", <$ty>::from_le_bytes(array)),
+            Endian::Big => format!("{:?}", <$ty>::from_be_bytes(array)),
+        }
+    }}
+}
+
+impl FieldType {
+    /// Returns the size of the field in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            FieldType::U8 | FieldType::I8 => 1,
+            FieldType::U16(_) | FieldType::I16(_) => 2,
+            FieldType::U32(_) | FieldType::I32(_) | FieldType::F32(_) => 4,
+            FieldType::U64(_) | FieldType::I64(_) | FieldType::F64(_) => 8,
+            FieldType::Bytes(len) => len,
+        }
+    }
+    /// Formats the value of the field stored in `bytes`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `bytes` doesn't match the size of the field.
+    pub fn decode(self, bytes: &[u8]) -> String {
+        assert_eq!(bytes.len(), self.size(), "wrong number of bytes for {:?}", self);
+        match self {
+            FieldType::U8 => bytes[0].to_string(),
+            FieldType::I8 => (bytes[0] as i8).to_string(),
+            FieldType::U16(e) => decode!(bytes, u16, 2, e),
+            FieldType::I16(e) => decode!(bytes, i16, 2, e),
+            FieldType::U32(e) => decode!(bytes, u32, 4, e),
+            FieldType::I32(e) => decode!(bytes, i32, 4, e),
+            FieldType::U64(e) => decode!(bytes, u64, 8, e),
+            FieldType::I64(e) => decode!(bytes, i64, 8, e),
+            FieldType::F32(e) => decode!(bytes, f32, 4, e),
+            FieldType::F64(e) => decode!(bytes, f64, 8, e),
+            FieldType::Bytes(_) => {
+                let mut result = String::with_capacity(2 * bytes.len());
+                for &b in bytes {
+                    write!(result, "{:02x}", b).unwrap();
+                }
+                result
+            }
+        }
+    }
+}
+
+/// A named field of a `Layout`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Field {
+    /// Name of the field.
+    pub name: String,
+    /// Offset of the field.
+    pub offset: usize,
+    /// Type of the field.
+    pub ty: FieldType,
+}
+
+impl Field {
+    /// Returns the byte range occupied by the field.
+    pub fn range(&self) -> ops::Range<usize> {
+        self.offset..self.offset + self.ty.size()
+    }
+    /// Formats the annotation label of the field for the given input.
+    pub(crate) fn label(&self, bytes: &[u8]) -> String {
+        match bytes.get(self.range()) {
+            Some(field) => format!("{} = {}", self.name, self.ty.decode(field)),
+            None => format!("{} = <truncated>", self.name),
+        }
+    }
+}
+
+/// Declarative description of a binary structure, see `Config::layout`.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+/// use hexdump::Endian::Little;
+/// use hexdump::FieldType;
+///
+/// let layout = hexdump::Layout::new()
+///     .field("magic", FieldType::Bytes(4))
+///     .field("length", FieldType::U32(Little))
+///     .field_at("flags", 12, FieldType::U16(Little));
+/// assert_eq!(layout.size(), 14);
+/// hexdump::Config::new().layout(layout).hexdump(b"\x7fELF\x10\0\0\0");
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Layout {
+    fields: Vec<Field>,
+}
+
+impl Layout {
+    /// Creates a layout without fields.
+    pub fn new() -> Layout {
+        Layout::default()
+    }
+    /// Adds a field directly after the end of all previous fields.
+    pub fn field<T: Into<String>>(self, name: T, ty: FieldType) -> Layout {
+        let offset = self.size();
+        self.field_at(name, offset, ty)
+    }
+    /// Adds a field at the given offset.
+    pub fn field_at<T: Into<String>>(mut self, name: T, offset: usize, ty: FieldType) -> Layout {
+        self.fields.push(Field { name: name.into(), offset, ty });
+        self
+    }
+    /// Returns the fields of the layout.
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+    /// Returns the end of the last field.
+    pub fn size(&self) -> usize {
+        self.fields.iter().map(|f| f.range().end).max().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Endian;
+    use super::FieldType;
+    use super::Layout;
+    use config::Config;
+
+    #[test]
+    fn test_layout() {
+        let layout = Layout::new()
+            .field("magic", FieldType::Bytes(2))
+            .field("version", FieldType::U8)
+            .field_at("length", 4, FieldType::U32(Endian::Little))
+            .field("missing", FieldType::U16(Endian::Big));
+        let bytes = b"MZ\x01\0\x10\x20\0\0\0";
+        let lines: Vec<_> = Config::new().layout(layout).hexdump_iter(bytes)
+            .map(|l| l.to_string()).collect();
+        assert_eq!(lines[1], " ^^^^ magic = 4d5a");
+        assert_eq!(lines[2], "     ^^ version = 1");
+        assert_eq!(lines[3], "          ^^^^^^^^ length = 8208");
+        assert_eq!(lines[4], "                   ^^ missing = <truncated>");
+        assert_eq!(lines.len(), 6);
+    }
+}
@@ -13,17 +13,187 @@
 //! |66|                                  f                00000010
 //!                                                        00000011
 //! ```
+//!
+//! # Output format
+//!
+//! The output format of the default configuration is stable: it only
+//! changes in semver-incompatible releases, so dumps can safely be used as
+//! golden snapshots, e.g. via `hexdump_string`.
+//!
+//! Each data line consists of
+//!
+//! * the hex representation of up to 16 bytes, in groups of 4 bytes,
+//!   enclosed in `|`,
+//! * padding so that all lines have the same width,
+//! * the bytes as text, with non-printable bytes replaced by `.` (see
+//!   `sanitize_byte`),
+//! * the offset of the first byte of the line as 8 or more lowercase hex
+//!   digits.
+//!
+//! The last line is the summary line which only contains the length of the
+//! data, aligned with the offset column.
 
 #![warn(missing_docs)]
 
 #[cfg(test)] #[macro_use] extern crate quickcheck;
 
-extern crate arrayvec;
+#[cfg(feature = "mmap")] extern crate memmap2;
+#[cfg(feature = "serde")] extern crate serde;
+#[cfg(feature = "terminal")] extern crate terminal_size;
+#[cfg(feature = "wasm")] extern crate wasm_bindgen;
 
+#[macro_use] mod macros;
+
+mod chunks;
+mod config;
+mod console;
+mod csv;
+mod diff;
+mod display;
+mod ebcdic;
+mod ext;
+mod fallible;
+#[cfg(feature = "ffi")] pub mod ffi;
+mod frame;
+mod frames;
+mod golden;
+mod hex;
+mod highlight;
 mod imp;
+mod inspect;
+mod layout;
+mod markdown;
+mod overview;
+#[cfg(feature = "mmap")] mod mmap;
+mod plain;
+#[cfg(all(feature = "process", target_os = "linux"))] mod process;
+mod reader;
+mod search;
+mod segments;
+mod stats;
+mod stream;
+mod strings;
+mod style;
+#[cfg(test)] mod testutil;
+mod trace;
+#[cfg(feature = "wasm")] pub mod wasm;
+mod watch;
+mod wireshark;
+mod writer;
+
+pub use chunks::Chunk;
+pub use chunks::Chunks;
+pub use chunks::hexdump_chunks;
+
+pub use config::ByteFormat;
+pub use config::Checksum;
+pub use config::ColorChoice;
+pub use config::Columns;
+pub use config::Config;
+pub use config::LineTerminator;
+pub use config::Sink;
+pub use config::TextEncoding;
+
+pub use csv::Csv;
+pub use csv::Quoting;
+pub use csv::hexdump_csv_iter;
+
+pub use diff::Diff;
+pub use diff::DiffRecord;
+pub use diff::DiffRecords;
+pub use diff::diff_records;
+pub use diff::hexdump_diff_iter;
+
+pub use display::Dump;
+pub use display::DumpDiff;
+pub use display::dump;
+pub use display::dump_diff;
+
+pub use ext::HexdumpExt;
+
+pub use fallible::OnError;
+pub use fallible::TryHexdump;
+pub use fallible::try_hexdump_iter;
+
+pub use frames::Frames;
+pub use frames::IntoFrame;
+pub use frames::hexdump_frames_iter;
+
+#[doc(hidden)]
+pub use golden::assert_bytes_matches_file;
+
+pub use hex::DecodeHexError;
+pub use hex::decode_hex;
+pub use hex::hexdump_from_hex;
+
+pub use layout::Endian;
+pub use layout::Field;
+pub use layout::FieldType;
+pub use layout::Layout;
+
+pub use markdown::hexdump_markdown;
+pub use markdown::hexdump_markdown_table;
+
+#[cfg(feature = "mmap")] pub use mmap::hexdump_file;
+#[cfg(feature = "mmap")] pub use mmap::hexdump_file_iter;
+
+pub use overview::Overview;
+pub use overview::hexdump_overview_iter;
+
+#[cfg(all(feature = "process", target_os = "linux"))] pub use process::ProcessMemory;
+#[cfg(all(feature = "process", target_os = "linux"))] pub use process::hexdump_process_iter;
+
+pub use plain::PlainHex;
+pub use plain::hexdump_plain_iter;
+
+pub use reader::HexdumpReader;
+
+pub use search::Search;
+pub use search::hexdump_search_iter;
+
+pub use segments::Segments;
+pub use segments::hexdump_segments_iter;
+
+pub use stream::Continuation;
+pub use stream::HexdumpStream;
+pub use stream::Live;
+pub use stream::hexdump_live_iter;
+
+pub use strings::Strings;
+pub use strings::hexdump_strings_iter;
+
+pub use style::Color;
+pub use style::Style;
+pub use style::Theme;
+
+pub use trace::Direction;
+pub use trace::Trace;
+pub use trace::TraceLines;
+pub use trace::Traced;
+
+pub use watch::Watch;
+pub use watch::hexdump_watch;
+
+pub use wireshark::Wireshark;
+pub use wireshark::hexdump_wireshark_iter;
+
+pub use writer::HexdumpWriter;
+
+pub use highlight::Highlight;
 
 pub use imp::Line;
 pub use imp::Hexdump;
+pub use imp::ParseLineError;
 pub use imp::hexdump;
+pub use imp::hexdump_eprint;
+pub use imp::hexdump_for_each;
 pub use imp::hexdump_iter;
+pub use imp::hexdump_lines;
+pub use imp::hexdump_owned_iter;
+pub use imp::hexdump_range_iter;
+pub use imp::hexdump_str_iter;
+pub use imp::hexdump_string;
+pub use imp::hexdump_tail_iter;
+pub use imp::hexdump_write;
 pub use imp::sanitize_byte;
+pub use imp::try_hexdump;
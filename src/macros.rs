@@ -0,0 +1,93 @@
+/// Prints a hexdump of the given expressions to stdout, titled with their
+/// source code and location.
+///
+/// Each expression must implement `AsRef<[u8]>` and is only borrowed. The
+/// title line has the form `---- [file:line] expression (n bytes) ----`.
+///
+/// # Example
+///
+/// ```
+/// #[macro_use] extern crate hexdump;
+///
+/// # fn main() {
+/// let header = [0x7f, b'E', b'L', b'F'];
+/// let payload = vec![1, 2, 3];
+/// hexdump!(header, payload);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! hexdump {
+    ($($expr:expr),+ $(,)?) => {{
+        $(
+            $crate::Config::new()
+                .title(format!("[{}:{}] {}", file!(), line!(), stringify!($expr)))
+                .hexdump(::std::convert::AsRef::<[u8]>::as_ref(&$expr));
+        )+
+    }};
+}
+
+/// Prints a hexdump of the value of the given expression to stderr and
+/// returns the value, like `dbg!`.
+///
+/// The value must deref to `[u8]`, e.g. `Vec<u8>`, `&[u8]` or `&Vec<u8>`. It
+/// is moved in and out again, so passing a reference doesn't move the
+/// referenced buffer. The dump is titled like the ones of `hexdump!`.
+///
+/// # Example
+///
+/// ```
+/// #[macro_use] extern crate hexdump;
+///
+/// # fn send(_: &[u8]) {}
+/// # fn main() {
+/// let frame = vec![0x02, b'h', b'i', 0x03];
+/// send(hexdbg!(&frame));
+/// assert_eq!(hexdbg!(frame).len(), 4);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! hexdbg {
+    ($expr:expr $(,)?) => {
+        match $expr {
+            value => {
+                {
+                    let bytes: &[u8] = &value;
+                    let title = format!("[{}:{}] {}", file!(), line!(), stringify!($expr));
+                    $crate::Config::new().title(title).sink($crate::Sink::Stderr).hexdump(bytes);
+                }
+                value
+            }
+        }
+    };
+}
+
+/// Asserts that the given bytes equal the contents of a golden file.
+///
+/// The bytes must implement `AsRef<[u8]>`. A relative path is resolved
+/// against the directory of the calling crate's `Cargo.toml`. On mismatch,
+/// the panic message shows the hexdumps of the lines around the first
+/// difference side by side, see `dump_diff`.
+///
+/// If the environment variable `HEXDUMP_UPDATE_GOLDEN` is set to anything
+/// but `0`, the bytes are written to the file instead, creating it and its
+/// parent directories if necessary.
+///
+/// # Example
+///
+/// ```no_run
+/// #[macro_use] extern crate hexdump;
+///
+/// # fn encode() -> Vec<u8> { Vec::new() }
+/// # fn main() {
+/// assert_bytes_matches_file!(encode(), "tests/golden/encoded.bin");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assert_bytes_matches_file {
+    ($bytes:expr, $path:expr $(,)?) => {
+        $crate::assert_bytes_matches_file(
+            ::std::convert::AsRef::<[u8]>::as_ref(&$bytes),
+            &::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($path),
+        )
+    };
+}
@@ -0,0 +1,473 @@
+//! Command line interface to the `hexdump` library.
+//!
+//! Requires the `cli` feature.
+
+extern crate hexdump;
+
+use hexdump::ByteFormat;
+use hexdump::Checksum;
+use hexdump::ColorChoice;
+use hexdump::Columns;
+use hexdump::Config;
+use hexdump::Endian;
+use hexdump::Highlight;
+use hexdump::LineTerminator;
+use hexdump::Sink;
+use hexdump::TextEncoding;
+use std::env;
+use std::fs;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::process;
+use std::str;
+
+const USAGE: &str = "\
+Usage: hexdump [OPTIONS] [FILE]
+
+Prints a hexdump of FILE, or of stdin if FILE is missing or `-`.
+
+Options:
+    -n, --cols N            show N bytes per line (a multiple of 4)
+        --fit               fit the lines to the terminal width
+    -f, --format FORMAT     show bytes as hex, binary, octal, decimal or
+                            signed-decimal
+        --delimiters OPEN CLOSE
+                            enclose the data column in OPEN and CLOSE
+        --segment-separator SEP
+                            separate groups of 4 bytes by SEP
+        --frame             frame the dump with box-drawing characters
+        --columns COLUMNS   show the columns data-text, text-data, data or text
+        --ruler             label the byte positions above the columns
+        --ruler-every N     repeat the ruler every N lines
+    -t, --text ENCODING     decode the text column as ascii, utf16le, utf16be,
+                            cp037 or cp500
+        --placeholder CHAR  show non-printable bytes as CHAR instead of `.`
+        --group-text        split the text column into groups of 4 characters
+    -s, --skip OFFSET       start the dump at OFFSET
+    -l, --length LENGTH     only dump LENGTH bytes
+        --tail LENGTH       only dump the last LENGTH bytes
+        --max-lines N       stop the dump after N lines
+        --head-tail N M     only show the first N and the last M lines
+        --collapse N        collapse runs of at least N equal bytes
+        --title TITLE       emit a title line before the dump
+        --normalize         show offsets relative to the first line
+        --size              show the size in the summary line
+        --base OFFSET       add OFFSET to the offsets shown
+        --align             align the lines to the offsets shown
+        --relative ANCHOR   show offsets relative to ANCHOR, with a sign
+        --entropy           append the entropy of each line
+        --stats             print statistics about the bytes after the dump
+        --overview          show a map of the byte classes before the dump
+        --heatmap           color the bytes by their value
+        --highlight RULE    color the bytes selected by RULE (nul, ff, non-ascii,
+                            utf8-errors or align:N), can be repeated
+        --color WHEN        use colors always, never or if stdout is a
+                            terminal (auto, the default)
+        --checksum KIND     append a checksum of each line (crc32, sum8, xor8)
+        --inspect OFFSET    interpret the bytes at OFFSET
+        --annotate START END LABEL
+                            label the bytes from START to END
+        --redact START END  mask the bytes from START to END
+        --search HEX        only show lines containing the hex string
+    -C, --context N         show N lines around search matches (default 1)
+        --diff OTHER        only show the lines that differ from file OTHER
+        --crlf              terminate lines with CR LF
+        --line-numbers      number the lines of the output
+    -p, --plain             print continuous hex digits, like `xxd -p`
+    -r, --reverse           convert plain hex digits back to bytes
+        --strings MIN       print the strings of at least MIN printable
+                            characters with their offsets, like `strings`
+        --utf8              mark the multi-byte characters of UTF-8 input
+    -h, --help              show this help
+
+Environment:
+    HEXDUMP_COLS=N          show N bytes per line by default
+    HEXDUMP_GROUP=0         don't separate the groups of 4 bytes by default
+    HEXDUMP_UPPER=1         use uppercase hex digits by default
+";
+
+struct Options {
+    config: Config,
+    path: Option<String>,
+    base_offset: usize,
+    skip: usize,
+    length: Option<usize>,
+    tail: Option<usize>,
+    search: Option<Vec<u8>>,
+    context: usize,
+    diff: Option<String>,
+    plain: bool,
+    reverse: bool,
+    strings: Option<usize>,
+    utf8: bool,
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("hexdump: {}", message);
+    eprintln!("Try `hexdump --help` for more information.");
+    process::exit(2);
+}
+
+fn parse_number(s: &str) -> usize {
+    let result = if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        usize::from_str_radix(hex, 16)
+    } else {
+        s.parse()
+    };
+    result.unwrap_or_else(|_| fail(&format!("invalid number `{}`", s)))
+}
+
+fn parse_args<I: Iterator<Item=String>>(mut args: I) -> Options {
+    let mut options = Options {
+        config: Config::from_env(),
+        path: None,
+        base_offset: 0,
+        skip: 0,
+        length: None,
+        tail: None,
+        search: None,
+        context: 1,
+        diff: None,
+        plain: false,
+        reverse: false,
+        strings: None,
+        utf8: false,
+    };
+    let value = |args: &mut I, name: &str| {
+        args.next().unwrap_or_else(|| fail(&format!("missing value for `{}`", name)))
+    };
+    while let Some(arg) = args.next() {
+        let config = options.config.clone();
+        options.config = match &*arg {
+            "-h" | "--help" => {
+                print!("{}", USAGE);
+                process::exit(0);
+            }
+            "-n" | "--cols" => {
+                let cols = parse_number(&value(&mut args, &arg));
+                if cols == 0 || !cols.is_multiple_of(4) {
+                    fail("the number of bytes per line must be a positive multiple of 4");
+                }
+                config.bytes_per_line(cols)
+            }
+            "-f" | "--format" => config.byte_format(match &*value(&mut args, &arg) {
+                "hex" => ByteFormat::Hex,
+                "binary" => ByteFormat::Binary,
+                "octal" => ByteFormat::Octal,
+                "decimal" => ByteFormat::Decimal,
+                "signed-decimal" => ByteFormat::SignedDecimal,
+                other => fail(&format!("unknown format `{}`", other)),
+            }),
+            "--delimiters" => {
+                let open = value(&mut args, &arg);
+                config.delimiters(open, value(&mut args, &arg))
+            }
+            "--segment-separator" => config.segment_separator(value(&mut args, &arg)),
+            "--frame" => config.frame(true),
+            "--columns" => config.columns(match &*value(&mut args, &arg) {
+                "data-text" => Columns::DataText,
+                "text-data" => Columns::TextData,
+                "data" => Columns::Data,
+                "text" => Columns::Text,
+                other => fail(&format!("unknown columns `{}`", other)),
+            }),
+            "--ruler" => config.ruler(true),
+            "--group-text" => config.group_text(true),
+            "--ruler-every" => {
+                let lines = parse_number(&value(&mut args, &arg));
+                if lines == 0 {
+                    fail("the ruler must be repeated after a positive number of lines");
+                }
+                config.ruler_every(lines)
+            }
+            "-t" | "--text" => config.text_encoding(match &*value(&mut args, &arg) {
+                "ascii" => TextEncoding::Ascii,
+                "utf16le" => TextEncoding::Utf16(Endian::Little),
+                "utf16be" => TextEncoding::Utf16(Endian::Big),
+                "cp037" => TextEncoding::Cp037,
+                "cp500" => TextEncoding::Cp500,
+                other => fail(&format!("unknown text encoding `{}`", other)),
+            }),
+            "--placeholder" => {
+                let value = value(&mut args, &arg);
+                let mut chars = value.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if !c.is_control() => config.placeholder(c),
+                    _ => fail(&format!("invalid placeholder `{}`", value)),
+                }
+            }
+            #[cfg(feature = "terminal")]
+            "--fit" => config.fit_to_terminal(),
+            #[cfg(not(feature = "terminal"))]
+            "--fit" => fail("`--fit` requires the `terminal` feature"),
+            "-s" | "--skip" => {
+                options.skip = parse_number(&value(&mut args, &arg));
+                config
+            }
+            "-l" | "--length" => {
+                options.length = Some(parse_number(&value(&mut args, &arg)));
+                config
+            }
+            "--max-lines" => config.max_lines(parse_number(&value(&mut args, &arg))),
+            "--head-tail" => {
+                let head = parse_number(&value(&mut args, &arg));
+                let tail = parse_number(&value(&mut args, &arg));
+                config.head_tail(head, tail)
+            }
+            "--collapse" => config.collapse_runs(parse_number(&value(&mut args, &arg))),
+            "--title" => config.title(value(&mut args, &arg)),
+            "--normalize" => config.normalize_offsets(true),
+            "--size" => config.summary_size(true),
+            "--relative" => config.relative_to(parse_number(&value(&mut args, &arg))),
+            "--align" => config.align_base_offset(true),
+            "--base" => {
+                options.base_offset = parse_number(&value(&mut args, &arg));
+                config.base_offset(options.base_offset)
+            }
+            "--entropy" => config.entropy(true),
+            "--stats" => config.statistics(true),
+            "--overview" => config.overview(true),
+            "--heatmap" => config.heatmap(true),
+            "--highlight" => {
+                let rule = value(&mut args, &arg);
+                let rule = match &*rule {
+                    "nul" => Highlight::NulRuns,
+                    "ff" => Highlight::FfRuns,
+                    "non-ascii" => Highlight::NonAscii,
+                    "utf8-errors" => Highlight::Utf8Errors,
+                    _ if rule.starts_with("align:") => match parse_number(&rule[6..]) {
+                        0 => fail("alignment must be positive"),
+                        n => Highlight::Aligned(n),
+                    },
+                    other => fail(&format!("unknown highlight rule `{}`", other)),
+                };
+                config.highlight_themed(rule)
+            }
+            "--color" => config.color(match &*value(&mut args, &arg) {
+                "auto" => ColorChoice::Auto,
+                "always" => ColorChoice::Always,
+                "never" => ColorChoice::Never,
+                other => fail(&format!("unknown color choice `{}`", other)),
+            }),
+            "--checksum" => config.checksum(match &*value(&mut args, &arg) {
+                "crc32" => Checksum::Crc32,
+                "sum8" => Checksum::Sum8,
+                "xor8" => Checksum::Xor8,
+                other => fail(&format!("unknown checksum `{}`", other)),
+            }),
+            "--inspect" => config.inspect(parse_number(&value(&mut args, &arg))),
+            "--annotate" => {
+                let start = parse_number(&value(&mut args, &arg));
+                let end = parse_number(&value(&mut args, &arg));
+                config.annotate(start..end, value(&mut args, &arg))
+            }
+            "--redact" => {
+                let start = parse_number(&value(&mut args, &arg));
+                let end = parse_number(&value(&mut args, &arg));
+                config.redact(start..end)
+            }
+            "--tail" => {
+                options.tail = Some(parse_number(&value(&mut args, &arg)));
+                config
+            }
+            "--search" => {
+                let needle = hexdump::decode_hex(&value(&mut args, &arg))
+                    .unwrap_or_else(|e| fail(&e.to_string()));
+                options.search = Some(needle);
+                config
+            }
+            "-C" | "--context" => {
+                options.context = parse_number(&value(&mut args, &arg));
+                config
+            }
+            "--diff" => {
+                options.diff = Some(value(&mut args, &arg));
+                config
+            }
+            "--crlf" => config.line_terminator(LineTerminator::CrLf),
+            "--line-numbers" => config.line_numbers(true),
+            "-p" | "--plain" => {
+                options.plain = true;
+                config
+            }
+            "-r" | "--reverse" => {
+                options.reverse = true;
+                config
+            }
+            "--strings" => {
+                match parse_number(&value(&mut args, &arg)) {
+                    0 => fail("minimum string length must be positive"),
+                    min_len => options.strings = Some(min_len),
+                }
+                config
+            }
+            "--utf8" => {
+                options.utf8 = true;
+                config
+            }
+            _ if arg.starts_with('-') && arg != "-" => {
+                fail(&format!("unknown option `{}`", arg))
+            }
+            _ => {
+                if options.path.is_some() {
+                    fail("more than one input file given");
+                }
+                options.path = Some(arg);
+                config
+            }
+        };
+    }
+    options
+}
+
+fn read_input(path: Option<&str>) -> io::Result<Vec<u8>> {
+    match path {
+        None | Some("-") => {
+            let mut result = Vec::new();
+            io::stdin().read_to_end(&mut result)?;
+            Ok(result)
+        }
+        Some(path) => fs::read(path),
+    }
+}
+
+fn input_error(path: &str, error: io::Error) -> ! {
+    eprintln!("hexdump: {}: {}", path, error);
+    process::exit(1);
+}
+
+fn open_input(path: Option<&str>) -> io::Result<Box<dyn Read>> {
+    match path {
+        None | Some("-") => Ok(Box::new(io::stdin())),
+        Some(path) => Ok(Box::new(fs::File::open(path)?)),
+    }
+}
+
+fn write_diff(options: &Options, other: &str) -> io::Result<()> {
+    let path = options.path.as_deref().unwrap_or("-");
+    let input = open_input(options.path.as_deref()).unwrap_or_else(|e| input_error(path, e));
+    let other = fs::File::open(other).unwrap_or_else(|e| input_error(other, e));
+
+    let mut error = None;
+    let lines = options.config
+        .hexdump_diff_iter(io::BufReader::new(input), io::BufReader::new(other))
+        .map_while(|line| line.map_err(|e| error = Some(e)).ok());
+    let stdout = io::stdout();
+    options.config.write_lines(stdout.lock(), lines)?;
+    error.map_or(Ok(()), Err)
+}
+
+fn write_tail(options: &Options, tail: usize) -> io::Result<()> {
+    let stdout = io::stdout();
+    match options.path.as_deref() {
+        None | Some("-") => {
+            let bytes = read_input(None).unwrap_or_else(|e| input_error("-", e));
+            let lines = options.config.hexdump_tail_iter(&bytes, tail);
+            options.config.write_lines(stdout.lock(), lines)
+        }
+        Some(path) => {
+            let file = fs::File::open(path).unwrap_or_else(|e| input_error(path, e));
+            options.config.write_tail(stdout.lock(), file, tail)
+        }
+    }
+}
+
+fn main() {
+    let mut options = parse_args(env::args().skip(1));
+    options.config = options.config.resolve_color(Sink::Stdout);
+    if options.plain && (options.diff.is_some() || options.tail.is_some()
+                         || options.search.is_some()) {
+        fail("`--plain` can't be combined with `--diff`, `--tail` or `--search`");
+    }
+    if options.reverse && (options.plain || options.diff.is_some() || options.tail.is_some()
+                           || options.search.is_some() || options.skip != 0
+                           || options.length.is_some()) {
+        fail("`--reverse` can't be combined with other modes, `--skip` or `--length`");
+    }
+    if options.strings.is_some() && (options.plain || options.reverse || options.diff.is_some()
+                                     || options.tail.is_some() || options.search.is_some()) {
+        fail("`--strings` can't be combined with other modes");
+    }
+    if options.utf8 && (options.plain || options.reverse || options.strings.is_some()
+                        || options.diff.is_some() || options.tail.is_some()
+                        || options.search.is_some() || options.skip != 0
+                        || options.length.is_some()) {
+        fail("`--utf8` can't be combined with other modes, `--skip` or `--length`");
+    }
+    if let Some(ref other) = options.diff {
+        if options.search.is_some() || options.skip != 0 || options.length.is_some() {
+            fail("`--diff` can't be combined with `--search`, `--skip` or `--length`");
+        }
+        exit_on_error(write_diff(&options, other));
+        return;
+    }
+    if let Some(tail) = options.tail {
+        if options.search.is_some() || options.skip != 0 || options.length.is_some() {
+            fail("`--tail` can't be combined with `--search`, `--skip` or `--length`");
+        }
+        exit_on_error(write_tail(&options, tail));
+        return;
+    }
+    let bytes = read_input(options.path.as_deref())
+        .unwrap_or_else(|e| input_error(options.path.as_deref().unwrap_or("-"), e));
+    if options.reverse {
+        let text = String::from_utf8(bytes).unwrap_or_else(|_| fail("input is not valid UTF-8"));
+        let bytes = hexdump::decode_hex(&text).unwrap_or_else(|e| fail(&e.to_string()));
+        exit_on_error(io::stdout().write_all(&bytes));
+        return;
+    }
+    if options.utf8 {
+        let text = str::from_utf8(&bytes).unwrap_or_else(|e| {
+            fail(&format!("input is not valid UTF-8 at offset {:#x}", e.valid_up_to()))
+        });
+        let lines = options.config.hexdump_str_iter(text);
+        exit_on_error(options.config.write_lines(io::stdout().lock(), lines));
+        return;
+    }
+    if options.skip > bytes.len() {
+        fail("offset beyond the end of the input");
+    }
+    let end = options.length
+        .map_or(bytes.len(), |l| bytes.len().min(options.skip.saturating_add(l)));
+
+    let stdout = io::stdout();
+    let result = match options.search {
+        None if options.strings.is_some() => {
+            let min_len = options.strings.unwrap();
+            // Skipped bytes end strings just like redacted ones.
+            let strings = options.config.clone().redact(0..options.skip)
+                .hexdump_strings_iter(&bytes[..end], min_len);
+            options.config.write_lines(stdout.lock(), strings)
+        }
+        None if options.plain => {
+            let lines = options.config.hexdump_plain_iter(&bytes[options.skip..end]);
+            options.config.write_lines(stdout.lock(), lines)
+        }
+        Some(ref needle) => {
+            // Only search the selected bytes, showing their offsets in the input.
+            let base_offset = options.base_offset.checked_add(options.skip)
+                .unwrap_or_else(|| fail("offset too large"));
+            let search = options.config.clone()
+                .base_offset(base_offset)
+                .hexdump_search_iter(&bytes[options.skip..end], needle, options.context);
+            options.config.write_lines(stdout.lock(), search)
+        }
+        None => {
+            let lines = options.config.hexdump_range_iter(&bytes, options.skip..end);
+            options.config.write_lines(stdout.lock(), lines)
+        }
+    };
+    exit_on_error(result);
+}
+
+fn exit_on_error(result: io::Result<()>) {
+    match result {
+        Ok(()) => {}
+        Err(ref e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+        Err(e) => {
+            eprintln!("hexdump: {}", e);
+            process::exit(1);
+        }
+    }
+}
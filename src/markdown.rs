@@ -0,0 +1,91 @@
+use config::Config;
+use imp::line_fields;
+use std::cmp;
+use std::fmt::Write;
+
+/// Returns the hexdump of the given bytes as a Markdown code block.
+///
+/// See `Config::hexdump_markdown` for details.
+pub fn hexdump_markdown(bytes: &[u8]) -> String {
+    Config::new().hexdump_markdown(bytes)
+}
+
+/// Returns the hexdump of the given bytes as a Markdown table.
+///
+/// See `Config::hexdump_markdown_table` for details.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+///
+/// assert_eq!(hexdump::hexdump_markdown_table(b"a|b"), "\
+/// | offset | hex | text |
+/// |--------|-----|------|
+/// | `00000000` | `61 7c 62` | `a\\|b` |
+/// ");
+/// ```
+pub fn hexdump_markdown_table(bytes: &[u8]) -> String {
+    Config::new().hexdump_markdown_table(bytes)
+}
+
+/// Returns the length of the longest run of backticks in `s`.
+fn longest_backtick_run(s: &str) -> usize {
+    s.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
+
+/// Wraps `s` in a code block fenced by enough backticks.
+pub(crate) fn code_block(s: &str) -> String {
+    let fence = "`".repeat(cmp::max(3, longest_backtick_run(s) + 1));
+    let mut result = String::with_capacity(s.len() + 2 * fence.len() + 8);
+    writeln!(result, "{}text", fence).unwrap();
+    result.push_str(s);
+    if !s.ends_with('\n') {
+        result.push('\n');
+    }
+    writeln!(result, "{}", fence).unwrap();
+    result
+}
+
+/// Formats `s` as a code span in a table cell.
+///
+/// Pipes are escaped, and spaces are added around contents that would
+/// otherwise lose leading or trailing spaces or merge with the fence.
+fn code_span(s: &str) -> String {
+    let fence = "`".repeat(longest_backtick_run(s) + 1);
+    let pad = s.starts_with([' ', '`']) || s.ends_with([' ', '`']);
+    let pad = if pad { " " } else { "" };
+    format!("{}{}{}{}{}", fence, pad, s.replace('|', "\\|"), pad, fence)
+}
+
+/// Formats the data lines of the given bytes as the rows of a Markdown
+/// table.
+pub(crate) fn table(config: &Config, bytes: &[u8]) -> String {
+    let mut result = String::from("| offset | hex | text |\n|--------|-----|------|\n");
+    let chunk_length = config.chunk_length();
+    for (i, chunk) in bytes.chunks(chunk_length).enumerate() {
+        let (offset, data, text) = line_fields(config, i * chunk_length, chunk);
+        writeln!(result, "| {} | {} | {} |", code_span(&offset), code_span(&data),
+                 code_span(&text)).unwrap();
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use config::Config;
+    use imp::hexdump_string;
+
+    #[test]
+    fn test_markdown() {
+        let block = Config::new().hexdump_markdown(b"````");
+        assert_eq!(block, format!("`````text\n{}`````\n", hexdump_string(b"````")));
+        assert!(Config::new().hexdump_markdown(b"ab").starts_with("```text\n|6162|"));
+
+        let table = Config::new().redact(1..2).hexdump_markdown_table(b" a`|");
+        assert_eq!(table.lines().nth(2).unwrap(),
+                   "| `00000000` | `20 xx 60 7c` | ``  *`\\| `` |");
+        let lines = Config::new().hexdump_markdown_table(&[0; 20]).lines().count();
+        assert_eq!(lines, 4);
+    }
+}
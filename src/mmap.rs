@@ -0,0 +1,56 @@
+use config::Config;
+use imp::Hexdump;
+use imp::Input;
+use memmap2::Mmap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Prints a hexdump of the given file to stdout.
+///
+/// See `Config::hexdump_file` for details.
+pub fn hexdump_file<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    Config::new().hexdump_file(path)
+}
+
+/// Creates a hexdump iterator over the lines of the given file.
+///
+/// See `Config::hexdump_file_iter` for details.
+pub fn hexdump_file_iter<P: AsRef<Path>>(path: P) -> io::Result<Hexdump<'static>> {
+    Config::new().hexdump_file_iter(path)
+}
+
+/// Maps the given file into memory.
+pub(crate) fn map<P: AsRef<Path>>(path: P) -> io::Result<Input<'static>> {
+    let file = fs::File::open(path)?;
+    // SAFETY: The mapping is only read. Modifying the file while it is
+    // mapped is documented as undefined behavior in
+    // `Config::hexdump_file_iter`.
+    let map = unsafe { Mmap::map(&file)? };
+    Ok(Input::Mapped(Arc::new(map)))
+}
+
+#[cfg(test)]
+mod test {
+    use config::Config;
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_hexdump_file_iter() {
+        use std::env;
+        use std::fs;
+        use std::process;
+        let bytes: Vec<u8> = (0..=255).collect();
+        let path = env::temp_dir().join(format!("hexdump-mmap-{}", process::id()));
+        fs::write(&path, &bytes).unwrap();
+        let lines: Vec<_> = Config::new().hexdump_file_iter(&path).unwrap().collect();
+        assert_eq!(lines, Config::new().hexdump_iter(&bytes).collect::<Vec<_>>());
+        let iter = Config::new().hexdump_file_range_iter(&path, 40..60).unwrap();
+        assert_eq!(iter.line_at(1), Config::new().hexdump_range_iter(&bytes, 40..60).line_at(1));
+        fs::write(&path, b"").unwrap();
+        assert_eq!(Config::new().hexdump_file_iter(&path).unwrap().count(), 1);
+        fs::remove_file(&path).unwrap();
+        assert!(Config::new().hexdump_file_iter(&path).is_err());
+    }
+}
@@ -0,0 +1,249 @@
+use config::Config;
+use imp::Buffer;
+use imp::Line;
+use std::cmp;
+use std::fmt::Write;
+use std::iter;
+
+/// Default number of cells per row.
+const COLUMNS: usize = 64;
+/// Number of rows the whole input fits into by default.
+const ROWS: usize = 16;
+
+/// Classes of bytes, in the order ties are resolved, with their glyph.
+///
+/// Their styles are those in `Theme::overview`.
+const CLASSES: [(char, &str); 5] = [
+    ('.', "00"),
+    ('a', "printable"),
+    ('_', "control"),
+    ('x', "high"),
+    ('#', "ff"),
+];
+
+fn class(b: u8) -> usize {
+    match b {
+        0x00 => 0,
+        0x20..=0x7e | b'\t' | b'\n' | b'\r' => 1,
+        0x01..=0x1f | 0x7f => 2,
+        0x80..=0xfe => 3,
+        0xff => 4,
+    }
+}
+
+/// Return type of `hexdump_overview_iter`.
+///
+/// Maps the whole input to a grid with one character per cell of bytes,
+/// showing the class of most of its bytes: `.` for zero bytes, `a` for
+/// printable ASCII, `_` for other control characters, `x` for bytes above
+/// 0x7f and `#` for 0xff bytes. Redacted bytes are left out, and cells of
+/// only redacted bytes show `*`. Each row starts with the offset of its
+/// first byte, and a legend stating the size of the cells comes last.
+#[derive(Clone, Debug)]
+pub struct Overview<'a> {
+    bytes: &'a [u8],
+    config: Config,
+    position: usize,
+    bytes_per_cell: usize,
+    columns: usize,
+    colored: bool,
+    front: usize,
+    back: usize,
+}
+
+/// Creates an iterator over the lines of an overview of the given bytes.
+///
+/// See `Config::hexdump_overview_iter` for details.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+///
+/// let mut bytes = vec![0; 64];
+/// bytes.extend_from_slice(&[b'a'; 64]);
+/// bytes.extend_from_slice(&[0xff; 32]);
+/// let lines: Vec<_> = hexdump::hexdump_overview_iter(&bytes)
+///     .bytes_per_cell(32)
+///     .columns(4)
+///     .map(|l| l.to_string())
+///     .collect();
+/// assert_eq!(lines, [
+///     "00000000 ..aa",
+///     "00000080 #",
+///     "1 cell = 32 bytes: . 00, a printable, _ control, x high, # ff",
+/// ]);
+/// ```
+pub fn hexdump_overview_iter(bytes: &[u8]) -> Overview<'_> {
+    Config::new().hexdump_overview_iter(bytes)
+}
+
+impl<'a> Overview<'a> {
+    /// Creates an overview of `bytes`, which start at the given position of
+    /// the dumped buffer.
+    pub(crate) fn new(bytes: &'a [u8], config: &Config, position: usize) -> Overview<'a> {
+        let bytes_per_cell = cmp::max(1, bytes.len().div_ceil(COLUMNS * ROWS));
+        let mut result = Overview {
+            bytes,
+            config: config.clone(),
+            position,
+            bytes_per_cell,
+            columns: COLUMNS,
+            colored: false,
+            front: 0,
+            back: 0,
+        };
+        result.back = result.num_lines();
+        result
+    }
+    /// Sets the number of bytes per cell.
+    ///
+    /// By default, the cells are just large enough for the grid to fit into
+    /// 16 rows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes_per_cell` is zero or if iteration has already
+    /// started.
+    pub fn bytes_per_cell(mut self, bytes_per_cell: usize) -> Overview<'a> {
+        assert!(bytes_per_cell != 0, "bytes per cell must be positive");
+        self.assert_unstarted();
+        self.bytes_per_cell = bytes_per_cell;
+        self.back = self.num_lines();
+        self
+    }
+    /// Sets the number of cells per row, 64 by default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `columns` is zero or if iteration has already started.
+    pub fn columns(mut self, columns: usize) -> Overview<'a> {
+        assert!(columns != 0, "columns must be positive");
+        self.assert_unstarted();
+        self.columns = columns;
+        self.back = self.num_lines();
+        self
+    }
+    /// Colors the cells by their class using ANSI escape sequences, see
+    /// `Config::theme`.
+    ///
+    /// Has no effect if colors are disabled by `Config::color`.
+    pub fn colored(mut self, colored: bool) -> Overview<'a> {
+        self.colored = colored && self.config.colors_enabled();
+        self
+    }
+    fn assert_unstarted(&self) {
+        assert!(self.front == 0 && self.back == self.num_lines(),
+                "overview must be configured before iterating");
+    }
+    fn num_lines(&self) -> usize {
+        let cells = self.bytes.len().div_ceil(self.bytes_per_cell);
+        cells.div_ceil(self.columns) + 1
+    }
+    fn line(&self, index: usize) -> Line {
+        let mut buf = Buffer::new();
+        if index == self.num_lines() - 1 {
+            write!(buf, "1 cell = {} bytes:", self.bytes_per_cell).unwrap();
+            for (i, &(glyph, name)) in CLASSES.iter().enumerate() {
+                let separator = if i == 0 { "" } else { "," };
+                write!(buf, "{} {} {}", separator, glyph, name).unwrap();
+            }
+            return Line::new(buf);
+        }
+        let row_length = self.bytes_per_cell * self.columns;
+        let start = index * row_length;
+        let end = cmp::min(start + row_length, self.bytes.len());
+        write!(buf, "{:08x} ", self.config.end_offset(self.position + start)).unwrap();
+        for (i, cell) in self.bytes[start..end].chunks(self.bytes_per_cell).enumerate() {
+            let position = self.position + start + i * self.bytes_per_cell;
+            let mut counts = [0; 5];
+            for (j, &b) in cell.iter().enumerate() {
+                if !self.config.is_redacted(position + j) {
+                    counts[class(b)] += 1;
+                }
+            }
+            if counts.iter().all(|&c| c == 0) {
+                buf.write_char('*').unwrap();
+                continue;
+            }
+            // The first of the most common classes.
+            let dominant = (0..counts.len()).rev().max_by_key(|&c| counts[c]).unwrap();
+            let (glyph, _) = CLASSES[dominant];
+            if self.colored {
+                let style = self.config.theme.overview[dominant];
+                style.write_start(&mut buf).unwrap();
+                buf.write_char(glyph).unwrap();
+                style.write_end(&mut buf).unwrap();
+            } else {
+                buf.write_char(glyph).unwrap();
+            }
+        }
+        Line::new(buf)
+    }
+}
+
+impl<'a> Iterator for Overview<'a> {
+    type Item = Line;
+    fn next(&mut self) -> Option<Line> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.line(self.front - 1))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
+}
+
+impl<'a> DoubleEndedIterator for Overview<'a> {
+    fn next_back(&mut self) -> Option<Line> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.line(self.back))
+    }
+}
+
+impl<'a> iter::FusedIterator for Overview<'a> {}
+
+impl<'a> ExactSizeIterator for Overview<'a> {
+    fn len(&self) -> usize {
+        self.back - self.front
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use config::ColorChoice;
+    use config::Config;
+
+    #[test]
+    fn test_overview() {
+        let mut bytes = vec![0x80; 0x7f0];
+        bytes.extend_from_slice(&[b'\n'; 0x10]);
+        let lines: Vec<_> = Config::new().hexdump_overview_iter(&bytes)
+            .map(|l| l.to_string())
+            .collect();
+        assert_eq!(lines.len(), 17);
+        assert_eq!(lines[0], format!("00000000 {}", "x".repeat(64)));
+        assert_eq!(lines[15], format!("00000780 {}{}", "x".repeat(56), "a".repeat(8)));
+        assert_eq!(lines[16].split(':').next(), Some("1 cell = 2 bytes"));
+
+        let config = Config::new().title("t").redact(0..4).base_offset(0x100);
+        let bytes = b"\0\0\0\0\0\0\x01\x01\x01";
+        let lines: Vec<_> = config.clone().overview(true).hexdump_range_iter(bytes, 2..9)
+            .map(|l| l.to_string())
+            .collect();
+        assert_eq!(lines[0], "---- t (7 bytes) ----");
+        assert_eq!(lines[1], "00000102 **..___");
+        assert!(lines[2].starts_with("1 cell = 1 bytes:"));
+        let dump: Vec<_> = config.hexdump_range_iter(bytes, 2..9).map(|l| l.to_string()).collect();
+        assert_eq!(lines[3..], dump[1..]);
+
+        let config = Config::new().color(ColorChoice::Always);
+        let colored = config.hexdump_overview_iter(b"\xff").colored(true).next().unwrap();
+        assert_eq!(colored.to_string(), "00000000 \x1b[37m#\x1b[0m");
+    }
+}
@@ -0,0 +1,119 @@
+use config::Config;
+use imp::Buffer;
+use imp::Line;
+use std::cmp;
+use std::fmt::Write;
+use std::iter;
+
+/// Default number of bytes per line, the same as that of `xxd -p`.
+pub(crate) const PLAIN_CHUNK_LENGTH: usize = 30;
+
+/// Return type of `hexdump_plain_iter`.
+///
+/// Yields the bytes as continuous hex digits without offsets or text, the
+/// format of `xxd -p`. `decode_hex` turns the lines back into bytes.
+#[derive(Clone, Debug)]
+pub struct PlainHex<'a> {
+    bytes: &'a [u8],
+    chunk_length: usize,
+    uppercase: bool,
+    front: usize,
+    back: usize,
+}
+
+/// Creates an iterator over the lines of a plain hex dump with 30 bytes per
+/// line, like `xxd -p`.
+///
+/// See `Config::hexdump_plain_iter` for details.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+///
+/// let bytes: Vec<u8> = (0..40).collect();
+/// let lines: Vec<_> = hexdump::hexdump_plain_iter(&bytes).map(|l| l.to_string()).collect();
+/// assert_eq!(lines, [
+///     "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d",
+///     "1e1f2021222324252627",
+/// ]);
+/// assert_eq!(hexdump::decode_hex(&lines.join("\n")).unwrap(), bytes);
+/// ```
+pub fn hexdump_plain_iter(bytes: &[u8]) -> PlainHex<'_> {
+    Config::new().hexdump_plain_iter(bytes)
+}
+
+impl<'a> PlainHex<'a> {
+    pub(crate) fn new(bytes: &'a [u8], config: &Config) -> PlainHex<'a> {
+        let chunk_length = config.plain_bytes_per_line;
+        PlainHex {
+            bytes,
+            chunk_length,
+            uppercase: config.uppercase,
+            front: 0,
+            back: bytes.len().div_ceil(chunk_length),
+        }
+    }
+    fn line(&self, index: usize) -> Line {
+        let start = index * self.chunk_length;
+        let end = cmp::min(start + self.chunk_length, self.bytes.len());
+        let mut buf = Buffer::new();
+        for &b in &self.bytes[start..end] {
+            if self.uppercase {
+                write!(buf, "{:02X}", b).unwrap();
+            } else {
+                write!(buf, "{:02x}", b).unwrap();
+            }
+        }
+        Line::new(buf)
+    }
+}
+
+impl<'a> Iterator for PlainHex<'a> {
+    type Item = Line;
+    fn next(&mut self) -> Option<Line> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.line(self.front - 1))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
+}
+
+impl<'a> DoubleEndedIterator for PlainHex<'a> {
+    fn next_back(&mut self) -> Option<Line> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.line(self.back))
+    }
+}
+
+impl<'a> iter::FusedIterator for PlainHex<'a> {}
+
+impl<'a> ExactSizeIterator for PlainHex<'a> {
+    fn len(&self) -> usize {
+        self.back - self.front
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use config::Config;
+    use hex::decode_hex;
+
+    quickcheck! {
+        fn plain_roundtrip(bytes: Vec<u8>, bytes_per_line: u8, uppercase: bool) -> bool {
+            let config = Config::new()
+                .plain_bytes_per_line(bytes_per_line as usize + 1)
+                .uppercase(uppercase);
+            let lines: Vec<_> = config.hexdump_plain_iter(&bytes).map(|l| l.to_string()).collect();
+            lines.iter().all(|l| l.len() <= 2 * (bytes_per_line as usize + 1))
+                && decode_hex(&lines.join("\n")).unwrap() == bytes
+        }
+    }
+}
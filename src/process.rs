@@ -0,0 +1,175 @@
+use config::Config;
+use imp::Buffer;
+use imp::Hexdump;
+use imp::Input;
+use imp::Line;
+use imp::hexdump_summary;
+use std::cmp;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::iter;
+use std::ops;
+use std::os::unix::fs::FileExt;
+use std::vec;
+
+/// Granularity of the reads, the smallest page size of Linux.
+const PAGE_SIZE: usize = 4096;
+
+/// Return type of `hexdump_process_iter`.
+///
+/// Yields the lines of the readable parts of a memory range of a process,
+/// with the virtual addresses of the bytes as offsets. Unreadable pages,
+/// e.g. unmapped ones, are replaced by a line like
+/// `... 8192 bytes unreadable (0x1000..0x3000) ...`. The summary line
+/// shows the end of the range.
+///
+/// The memory is read when the iterator is created, so the dump is a
+/// snapshot, though not an atomic one if the process keeps running.
+pub struct ProcessMemory {
+    config: Config,
+    regions: vec::IntoIter<Region>,
+    current: Option<Hexdump<'static>>,
+    summary: Option<Line>,
+}
+
+/// Part of the memory range, made of whole pages except at its ends.
+#[derive(Clone, Debug)]
+enum Region {
+    Readable(usize, Vec<u8>),
+    Unreadable(ops::Range<usize>),
+}
+
+/// Creates an iterator over the lines of a hexdump of the given memory
+/// range of another process.
+///
+/// See `Config::hexdump_process_iter` for details.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+/// use std::process;
+///
+/// let secret = *b"process memory";
+/// let address = secret.as_ptr() as usize;
+/// let mut lines = hexdump::hexdump_process_iter(process::id(), address..address + 14)
+///     .unwrap();
+/// assert!(lines.next().unwrap().contains(" process memory "));
+/// ```
+pub fn hexdump_process_iter(pid: u32, range: ops::Range<usize>) -> io::Result<ProcessMemory> {
+    Config::new().hexdump_process_iter(pid, range)
+}
+
+impl ProcessMemory {
+    pub(crate) fn new(config: &Config, pid: u32, range: ops::Range<usize>)
+        -> io::Result<ProcessMemory>
+    {
+        assert!(range.start <= range.end, "invalid memory range {:?}", range);
+        let file = fs::File::open(format!("/proc/{}/mem", pid))?;
+        let mut regions: Vec<Region> = Vec::new();
+        let mut address = range.start;
+        while address < range.end {
+            let end = cmp::min((address / PAGE_SIZE + 1) * PAGE_SIZE, range.end);
+            let mut page = vec![0; end - address];
+            match read_page(&file, address, &mut page) {
+                Ok(()) => match regions.last_mut() {
+                    Some(&mut Region::Readable(_, ref mut bytes)) => bytes.extend(page),
+                    _ => regions.push(Region::Readable(address, page)),
+                },
+                Err(_) => match regions.last_mut() {
+                    Some(&mut Region::Unreadable(ref mut gap)) => gap.end = end,
+                    _ => regions.push(Region::Unreadable(address..end)),
+                },
+            }
+            address = end;
+        }
+
+        let mut config = config.clone();
+        config.title = None;
+        config.max_lines = None;
+        config.base_offset = 0;
+        let summary = hexdump_summary(&config, range.end, range.end - range.start);
+        Ok(ProcessMemory {
+            config,
+            regions: regions.into_iter(),
+            current: None,
+            summary: Some(summary),
+        })
+    }
+}
+
+/// Fills `page` with the bytes at the given address, failing if any of
+/// them can't be read.
+fn read_page(file: &fs::File, address: usize, page: &mut [u8]) -> io::Result<()> {
+    let mut read = 0;
+    while read < page.len() {
+        match file.read_at(&mut page[read..], (address + read) as u64) {
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(n) => read += n,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+impl Iterator for ProcessMemory {
+    type Item = Line;
+    fn next(&mut self) -> Option<Line> {
+        loop {
+            if let Some(ref mut current) = self.current {
+                // Skip the summary line of the region.
+                if current.len() > 1 {
+                    return current.next();
+                }
+            }
+            self.current = None;
+            match self.regions.next() {
+                Some(Region::Readable(address, bytes)) => {
+                    let len = bytes.len();
+                    let config = self.config.clone().base_offset(address);
+                    self.current = Some(Hexdump::from_input(Input::Owned(bytes.into()), config,
+                                                            0..len));
+                }
+                Some(Region::Unreadable(gap)) => {
+                    let mut buf = Buffer::new();
+                    write!(buf, "... {} bytes unreadable ({:#x}..{:#x}) ...",
+                           gap.end - gap.start, gap.start, gap.end).unwrap();
+                    return Some(Line::new(buf));
+                }
+                None => return self.summary.take(),
+            }
+        }
+    }
+}
+
+impl iter::FusedIterator for ProcessMemory {}
+
+#[cfg(test)]
+mod test {
+    use config::Config;
+
+    #[cfg(all(feature = "process", target_os = "linux"))]
+    #[test]
+    fn test_process_memory() {
+        use std::process;
+        let bytes: Vec<u8> = (0..100).collect();
+        let address = bytes.as_ptr() as usize;
+        let lines: Vec<_> = Config::new().base_offset(5)
+            .hexdump_process_iter(process::id(), address..address + bytes.len())
+            .unwrap()
+            .collect();
+        let expected: Vec<_> = Config::new().base_offset(address).hexdump_iter(&bytes).collect();
+        assert_eq!(lines, expected);
+
+        // The first page is never mapped.
+        let lines: Vec<_> = Config::new().hexdump_process_iter(process::id(), 0x10..0x2000)
+            .unwrap()
+            .map(|l| l.to_string())
+            .collect();
+        assert_eq!(lines[0], "... 8176 bytes unreadable (0x10..0x2000) ...");
+        assert!(lines[1].ends_with(" 00002000"));
+        assert!(Config::new().hexdump_process_iter(u32::MAX, 0..1).is_err());
+    }
+}
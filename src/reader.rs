@@ -0,0 +1,101 @@
+use config::Config;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use writer::SinkDump;
+
+/// Reader that passes through everything read from an inner reader while
+/// writing a hexdump of the bytes to a sink.
+///
+/// The dump is written in complete lines with the offsets of the bytes in
+/// the whole stream, see `HexdumpStream`. The remaining bytes of a partial
+/// line and the summary line are only written by `finish`, or when the
+/// reader is dropped. Created by `Config::hexdump_reader`.
+///
+/// Failing to write the dump doesn't fail the read, whose bytes would be
+/// lost otherwise. Instead, the dump skips lines until the error is
+/// returned by `finish`. Errors while writing the rest of the dump on drop
+/// are ignored, call `finish` to handle them.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+/// use std::io::Read;
+///
+/// let mut dump = Vec::new();
+/// let mut reader = hexdump::Config::new().hexdump_reader(&b"\x02hello\x03"[..], &mut dump);
+/// let mut frame = Vec::new();
+/// reader.read_to_end(&mut frame).unwrap();
+/// reader.finish().unwrap();
+/// assert_eq!(dump, hexdump::hexdump_string(&frame).into_bytes());
+/// ```
+#[derive(Debug)]
+pub struct HexdumpReader<R, S: Write> {
+    inner: R,
+    dump: SinkDump<S>,
+}
+
+impl<R: Read, S: Write> HexdumpReader<R, S> {
+    pub(crate) fn new(config: &Config, inner: R, sink: S) -> HexdumpReader<R, S> {
+        HexdumpReader {
+            inner,
+            dump: SinkDump::new(config, sink),
+        }
+    }
+    /// Returns a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+    /// Returns a mutable reference to the inner reader.
+    ///
+    /// Bytes read directly from it don't show up in the dump.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+    /// Returns the number of bytes read so far.
+    pub fn offset(&self) -> usize {
+        self.dump.offset()
+    }
+    /// Writes the rest of the dump to the sink and returns the inner
+    /// reader and the sink.
+    ///
+    /// Returns the first error writing the dump, if any.
+    pub fn finish(self) -> io::Result<(R, S)> {
+        let sink = self.dump.finish()?;
+        Ok((self.inner, sink))
+    }
+}
+
+impl<R: Read, S: Write> Read for HexdumpReader<R, S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.dump.push(&buf[..read]);
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use config::Config;
+    use imp::hexdump_string;
+    use testutil::Closed;
+
+    use std::io::Read;
+    use std::io;
+
+    #[test]
+    fn test_hexdump_reader_sink_error() {
+        let bytes: Vec<u8> = (0..40).collect();
+        let mut reader = Config::new().hexdump_reader(&bytes[..], Closed);
+        let mut read = Vec::new();
+        reader.read_to_end(&mut read).unwrap();
+        assert_eq!(read, bytes);
+        assert_eq!(reader.finish().unwrap_err().kind(), io::ErrorKind::BrokenPipe);
+
+        let mut dump = Vec::new();
+        io::copy(&mut Config::new().hexdump_reader(&bytes[..], &mut dump), &mut io::sink())
+            .unwrap();
+        assert_eq!(dump, hexdump_string(&bytes).into_bytes());
+    }
+}
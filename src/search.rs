@@ -0,0 +1,137 @@
+use config::Config;
+use imp::Buffer;
+use imp::Hexdump;
+use imp::Line;
+use std::cmp;
+use std::fmt::Write;
+use std::ops;
+use std::vec;
+
+/// Return type of `hexdump_search_iter`.
+///
+/// Yields the lines containing matches of the needle, surrounded by context
+/// lines. Each match is marked like an annotation labeled `match`. Groups of
+/// lines that are not adjacent are separated by a `--` line, and the summary
+/// line of the whole input comes last.
+pub struct Search<'a> {
+    bytes: &'a [u8],
+    config: Config,
+    matches: Vec<ops::Range<usize>>,
+    groups: vec::IntoIter<ops::Range<usize>>,
+    current: Option<Hexdump<'a>>,
+    separator: bool,
+    summary: Option<Line>,
+}
+
+/// Creates an iterator that only yields the lines containing `needle`, plus
+/// `context` lines before and after them.
+///
+/// See `Config::hexdump_search_iter` for details.
+pub fn hexdump_search_iter<'a>(bytes: &'a [u8], needle: &[u8], context: usize) -> Search<'a> {
+    Config::new().hexdump_search_iter(bytes, needle, context)
+}
+
+impl<'a> Search<'a> {
+    pub(crate) fn new(bytes: &'a [u8], config: &Config, needle: &[u8], context: usize)
+        -> Search<'a>
+    {
+        let mut config = config.clone();
+        config.title = None;
+        config.max_lines = None;
+        config.normalize_offsets = false;
+        let chunk_length = config.chunk_length();
+
+        let matches: Vec<_> = if needle.is_empty() {
+            Vec::new()
+        } else {
+            bytes.windows(needle.len()).enumerate()
+                .filter(|&(_, w)| w == needle)
+                .map(|(i, _)| i..i + needle.len())
+                .collect()
+        };
+
+        let mut groups: Vec<ops::Range<usize>> = Vec::new();
+        for m in &matches {
+            let first = (m.start / chunk_length).saturating_sub(context);
+            let last = (m.end - 1) / chunk_length + context + 1;
+            let range = first * chunk_length..cmp::min(last * chunk_length, bytes.len());
+            match groups.last_mut() {
+                Some(g) if range.start <= g.end => g.end = cmp::max(g.end, range.end),
+                _ => groups.push(range),
+            }
+        }
+
+        let summary = Hexdump::with_range(bytes, config.clone(), bytes.len()..bytes.len()).last();
+        Search {
+            bytes,
+            config,
+            matches,
+            groups: groups.into_iter(),
+            current: None,
+            separator: false,
+            summary,
+        }
+    }
+}
+
+impl<'a> Iterator for Search<'a> {
+    type Item = Line;
+    fn next(&mut self) -> Option<Line> {
+        loop {
+            if let Some(ref mut current) = self.current {
+                // Skip the summary line of the group.
+                if current.len() > 1 {
+                    return current.next();
+                }
+            }
+            self.current = None;
+            let group = match self.groups.next() {
+                Some(group) => group,
+                None => return self.summary.take(),
+            };
+            let mut config = self.config.clone();
+            config.annotations.extend(self.matches.iter()
+                .filter(|m| m.start < group.end && group.start < m.end)
+                .map(|m| (m.clone(), "match".to_owned())));
+            self.current = Some(Hexdump::with_range(self.bytes, config, group));
+            if self.separator {
+                let mut buf = Buffer::new();
+                buf.write_str("--").unwrap();
+                return Some(Line::new(buf));
+            }
+            self.separator = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::hexdump_search_iter;
+    use hex::decode_hex;
+    use imp::hexdump_iter;
+
+    #[test]
+    fn test_search() {
+        let mut bytes = vec![0u8; 0x100];
+        bytes[0x13..0x15].copy_from_slice(b"ab");
+        bytes[0x2f..0x31].copy_from_slice(b"ab");
+        bytes[0xa0..0xa2].copy_from_slice(b"ab");
+        let needle = decode_hex("0x6162").unwrap();
+        let lines: Vec<_> = hexdump_search_iter(&bytes, &needle, 1)
+            .map(|l| l.to_string()).collect();
+        let all: Vec<_> = hexdump_iter(&bytes).map(|l| l.to_string()).collect();
+        assert_eq!(lines[..2], all[..2]);
+        assert_eq!(lines[2], "       ^^^^^ match");
+        assert_eq!(lines[3], all[2]);
+        assert_eq!(lines[4], "                                  ^^ match");
+        assert_eq!(lines[5], all[3]);
+        assert_eq!(lines[6], " ^^ match (cont.)");
+        assert_eq!(lines[7], all[4]);
+        assert_eq!(lines[8], "--");
+        assert_eq!(lines[9..11], all[9..11]);
+        assert_eq!(lines[12], all[11]);
+        assert_eq!(lines[13], all[16]);
+        assert_eq!(lines.len(), 14);
+        assert_eq!(hexdump_search_iter(&bytes, b"abc", 1).count(), 1);
+    }
+}
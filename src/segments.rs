@@ -0,0 +1,128 @@
+use config::Config;
+use imp::Buffer;
+use imp::Hexdump;
+use imp::Line;
+use imp::hexdump_summary;
+use std::fmt::Write;
+
+/// Return type of `hexdump_segments_iter`.
+///
+/// Yields the lines of each segment with the offsets of its bytes. Segments
+/// that are not adjacent are separated by a line stating the size of the
+/// gap, and the summary line showing the end of the last segment comes
+/// last.
+///
+/// Options referring to positions, like annotations and redactions, apply
+/// to the positions within each segment. Titles, overviews and framing are
+/// ignored.
+///
+/// # Panics
+///
+/// Panics while iterating if the segments are not sorted by offset or if
+/// they overlap.
+pub struct Segments<'a, I> {
+    config: Config,
+    segments: I,
+    current: Option<Hexdump<'a>>,
+    end: Option<usize>,
+    len: usize,
+    done: bool,
+}
+
+/// Creates an iterator over the lines of a sparse input given as segments
+/// of bytes at the given offsets.
+///
+/// See `Config::hexdump_segments_iter` for details.
+pub fn hexdump_segments_iter<'a, I>(segments: I) -> Segments<'a, I::IntoIter>
+    where I: IntoIterator<Item = (usize, &'a [u8])>
+{
+    Config::new().hexdump_segments_iter(segments)
+}
+
+impl<'a, I> Segments<'a, I> {
+    pub(crate) fn new(config: &Config, segments: I) -> Segments<'a, I> {
+        let mut config = config.clone();
+        config.title = None;
+        config.normalize_offsets = false;
+        config.frame = false;
+        config.statistics = false;
+        config.overview = false;
+        Segments {
+            config,
+            segments,
+            current: None,
+            end: None,
+            len: 0,
+            done: false,
+        }
+    }
+}
+
+impl<'a, I: Iterator<Item = (usize, &'a [u8])>> Iterator for Segments<'a, I> {
+    type Item = Line;
+    fn next(&mut self) -> Option<Line> {
+        loop {
+            if let Some(ref mut current) = self.current {
+                // Skip the summary line of the segment.
+                if current.len() > 1 {
+                    return current.next();
+                }
+            }
+            self.current = None;
+            let (offset, bytes) = match self.segments.next() {
+                Some(segment) => segment,
+                None if self.done => return None,
+                None => {
+                    self.done = true;
+                    let end = self.end.unwrap_or(0);
+                    return Some(hexdump_summary(&self.config, self.config.end_offset(end),
+                                                self.len));
+                }
+            };
+            let previous_end = self.end;
+            if let Some(end) = previous_end {
+                assert!(end <= offset, "segment at {:#x} overlaps the previous one ending at {:#x}",
+                        offset, end);
+            }
+            let mut config = self.config.clone();
+            config.base_offset = config.end_offset(offset);
+            self.current = Some(Hexdump::new(bytes, config));
+            self.end = Some(offset + bytes.len());
+            self.len += bytes.len();
+            match previous_end {
+                Some(end) if end < offset => {
+                    let mut buf = Buffer::new();
+                    write!(buf, "... {:#x} bytes unmapped ...", offset - end).unwrap();
+                    return Some(Line::new(buf));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::hexdump_segments_iter;
+
+    #[test]
+    fn test_segments() {
+        let segments = vec![(0x10, &b"abc"[..]), (0x13, &b"d"[..]), (0x2000, &b"xy"[..])];
+        let lines: Vec<_> = hexdump_segments_iter(segments).map(|l| l.to_string()).collect();
+        assert_eq!(lines, [
+            "|616263|                              abc              00000010",
+            "|64|                                  d                00000013",
+            "... 0x1fec bytes unmapped ...",
+            "|7879|                                xy               00002000",
+            "                                                       00002002",
+        ]);
+        assert_eq!(hexdump_segments_iter(vec![]).map(|l| l.to_string()).collect::<Vec<_>>(),
+                   [format!("{:55}00000000", "")]);
+    }
+
+    #[test]
+    #[should_panic(expected = "overlaps")]
+    fn test_segments_overlap() {
+        hexdump_segments_iter(vec![(0, &b"abc"[..]), (2, &b"d"[..])]).count();
+    }
+}
@@ -0,0 +1,84 @@
+use imp::Buffer;
+use imp::Line;
+use imp::entropy_of_counts;
+use std::cmp;
+use std::fmt::Write;
+
+/// Number of most common bytes listed.
+const MOST_COMMON: usize = 3;
+
+/// Byte histogram of the dumped data, see `Config::statistics`.
+#[derive(Clone, Debug)]
+pub(crate) struct Statistics {
+    counts: [usize; 256],
+    len: usize,
+}
+
+impl Statistics {
+    pub(crate) fn new() -> Statistics {
+        Statistics { counts: [0; 256], len: 0 }
+    }
+    pub(crate) fn add(&mut self, byte: u8) {
+        self.counts[byte as usize] += 1;
+        self.len += 1;
+    }
+    fn percentage(&self, count: usize) -> f64 {
+        if self.len == 0 {
+            return 0.0;
+        }
+        count as f64 * 100.0 / self.len as f64
+    }
+    /// Formats the statistics block shown after the summary line.
+    pub(crate) fn lines(&self) -> Vec<Line> {
+        let printable = self.counts.iter().enumerate()
+            .filter(|&(b, _)| (0x20..0x7f).contains(&b) || b"\t\n\r".contains(&(b as u8)))
+            .map(|(_, &c)| c)
+            .sum();
+
+        let mut result = Vec::with_capacity(3);
+        let mut buf = Buffer::new();
+        write!(buf, "statistics: {} bytes, entropy {:.2} bits/byte",
+               self.len, entropy_of_counts(&self.counts, self.len)).unwrap();
+        result.push(Line::new(buf));
+
+        let mut buf = Buffer::new();
+        write!(buf, "printable: {:.1}%, zero: {:.1}%",
+               self.percentage(printable), self.percentage(self.counts[0])).unwrap();
+        result.push(Line::new(buf));
+
+        let mut common: Vec<_> = (0..256).filter(|&b| self.counts[b] != 0).collect();
+        // Stable sort, so ties are ordered by byte value.
+        common.sort_by_key(|&b| cmp::Reverse(self.counts[b]));
+        let mut buf = Buffer::new();
+        buf.write_str("most common:").unwrap();
+        if common.is_empty() {
+            buf.write_str(" none").unwrap();
+        }
+        for (i, &b) in common.iter().take(MOST_COMMON).enumerate() {
+            let separator = if i == 0 { "" } else { "," };
+            write!(buf, "{} {:02x} ({}, {:.1}%)",
+                   separator, b, self.counts[b], self.percentage(self.counts[b])).unwrap();
+        }
+        result.push(Line::new(buf));
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use config::Config;
+
+    #[test]
+    fn test_statistics() {
+        let config = Config::new().statistics(true).redact(4..6);
+        let lines: Vec<_> = config.hexdump_iter(b"aab\0\xff\xff").map(|l| l.to_string()).collect();
+        assert_eq!(&lines[2..], [
+            "statistics: 4 bytes, entropy 1.50 bits/byte",
+            "printable: 75.0%, zero: 25.0%",
+            "most common: 61 (2, 50.0%), 00 (1, 25.0%), 62 (1, 25.0%)",
+        ]);
+        let mut iter = config.hexdump_iter(b"");
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.nth(3).unwrap().to_string(), "most common: none");
+    }
+}
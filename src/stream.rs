@@ -0,0 +1,287 @@
+use config::Config;
+use imp::Hexdump;
+use imp::Line;
+use imp::format_line;
+use imp::hexdump_summary;
+use std::iter;
+use std::vec;
+
+/// Push-based hexdump of data arriving in pieces.
+///
+/// Bytes passed to `push` are accumulated into complete lines, so the
+/// output is the same as that of a hexdump of all the bytes at once,
+/// regardless of how the data was split. Only options affecting individual
+/// lines and `Config::progress` apply, titles, line limits, annotations and
+/// statistics are ignored.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+///
+/// let mut stream = hexdump::HexdumpStream::new();
+/// let mut lines: Vec<_> = stream.push(b"0123456789").collect();
+/// assert!(lines.is_empty());
+/// lines.extend(stream.push(b"abcdefghij"));
+/// lines.extend(stream.finish());
+/// assert_eq!(lines, hexdump::hexdump_iter(b"0123456789abcdefghij").collect::<Vec<_>>());
+/// ```
+#[derive(Clone, Debug)]
+pub struct HexdumpStream {
+    config: Config,
+    pending: Vec<u8>,
+    offset: usize,
+}
+
+impl HexdumpStream {
+    /// Creates a stream using the default configuration.
+    pub fn new() -> HexdumpStream {
+        HexdumpStream::with_config(&Config::new())
+    }
+    pub(crate) fn with_config(config: &Config) -> HexdumpStream {
+        HexdumpStream {
+            config: config.clone(),
+            pending: Vec::with_capacity(config.chunk_length()),
+            offset: 0,
+        }
+    }
+    /// Returns the number of bytes pushed so far.
+    pub fn len(&self) -> usize {
+        self.offset + self.pending.len()
+    }
+    /// Returns whether no bytes have been pushed so far.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Appends bytes to the stream, returning the lines completed by them.
+    ///
+    /// Panics if the offsets overflow, see `Config::base_offset`.
+    pub fn push(&mut self, mut bytes: &[u8]) -> vec::IntoIter<Line> {
+        self.config.end_offset(self.len() + bytes.len());
+        let chunk_length = self.config.chunk_length();
+        let mut lines = Vec::new();
+        if !self.pending.is_empty() {
+            let missing = chunk_length - self.pending.len();
+            if bytes.len() < missing {
+                self.pending.extend_from_slice(bytes);
+                return lines.into_iter();
+            }
+            self.pending.extend_from_slice(&bytes[..missing]);
+            bytes = &bytes[missing..];
+            lines.push(format_line(&self.config, self.offset, &self.pending));
+            self.pending.clear();
+            self.offset += chunk_length;
+            self.report_progress();
+        }
+        let mut chunks = bytes.chunks_exact(chunk_length);
+        for chunk in &mut chunks {
+            lines.push(format_line(&self.config, self.offset, chunk));
+            self.offset += chunk_length;
+            self.report_progress();
+        }
+        self.pending.extend_from_slice(chunks.remainder());
+        lines.into_iter()
+    }
+    /// Ends the stream, returning the line of the remaining bytes, if any,
+    /// and the summary line.
+    pub fn finish(self) -> vec::IntoIter<Line> {
+        let mut lines = Vec::with_capacity(2);
+        if !self.pending.is_empty() {
+            lines.push(format_line(&self.config, self.offset, &self.pending));
+        }
+        lines.push(hexdump_summary(&self.config, self.config.end_offset(self.len()),
+                                   self.len()));
+        if let Some((_, ref progress)) = self.config.progress {
+            (progress.0)(self.len(), Some(self.len()));
+        }
+        lines.into_iter()
+    }
+    /// Returns the line of the bytes of a partial line, if any, so that the
+    /// following bytes start a new line.
+    pub(crate) fn flush(&mut self) -> Option<Line> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        let line = format_line(&self.config, self.offset, &self.pending);
+        self.offset += self.pending.len();
+        self.pending.clear();
+        Some(line)
+    }
+    /// Calls the progress callback if a multiple of the configured number
+    /// of lines is complete.
+    fn report_progress(&self) {
+        if let Some((every, ref progress)) = self.config.progress {
+            if (self.offset / self.config.chunk_length()).is_multiple_of(every) {
+                (progress.0)(self.offset, None);
+            }
+        }
+    }
+}
+
+impl Default for HexdumpStream {
+    fn default() -> HexdumpStream {
+        HexdumpStream::new()
+    }
+}
+
+/// Return type of `hexdump_live_iter`.
+///
+/// Dumps the buffers of a blocking source like a channel as they arrive,
+/// with the offsets of their bytes within the whole stream. Each buffer is
+/// shown completely before the next one is waited for, so a buffer ending
+/// in a partial line is followed by one starting at the offset after it.
+/// The summary line comes once the source is exhausted, e.g. when all
+/// senders of a channel are dropped. As with `HexdumpStream`, only options
+/// affecting individual lines and `Config::progress` apply.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+/// use std::sync::mpsc;
+/// use std::thread;
+///
+/// let (sender, receiver) = mpsc::channel();
+/// thread::spawn(move || {
+///     sender.send(b"first payload".to_vec()).unwrap();
+///     sender.send(b"second".to_vec()).unwrap();
+/// });
+/// let lines: Vec<_> = hexdump::hexdump_live_iter(receiver).map(|l| l.to_string()).collect();
+/// assert!(lines[0].ends_with("first payload    00000000"));
+/// assert!(lines[1].ends_with("second           0000000d"));
+/// assert!(lines[2].ends_with(" 00000013"));
+/// ```
+#[derive(Debug)]
+pub struct Live<I> {
+    buffers: I,
+    stream: Option<HexdumpStream>,
+    lines: vec::IntoIter<Line>,
+}
+
+/// Creates an iterator dumping the buffers of a blocking source as they
+/// arrive, e.g. from a `std::sync::mpsc::Receiver<Vec<u8>>`.
+///
+/// See `Config::hexdump_live_iter` for details.
+pub fn hexdump_live_iter<I>(buffers: I) -> Live<I::IntoIter>
+    where I: IntoIterator, I::Item: AsRef<[u8]>
+{
+    Config::new().hexdump_live_iter(buffers)
+}
+
+impl<I> Live<I> {
+    pub(crate) fn new(config: &Config, buffers: I) -> Live<I> {
+        Live {
+            buffers,
+            stream: Some(config.hexdump_stream()),
+            lines: Vec::new().into_iter(),
+        }
+    }
+}
+
+impl<I: Iterator> Iterator for Live<I> where I::Item: AsRef<[u8]> {
+    type Item = Line;
+    fn next(&mut self) -> Option<Line> {
+        loop {
+            if let Some(line) = self.lines.next() {
+                return Some(line);
+            }
+            let stream = self.stream.as_mut()?;
+            self.lines = match self.buffers.next() {
+                Some(buffer) => {
+                    let mut lines: Vec<_> = stream.push(buffer.as_ref()).collect();
+                    lines.extend(stream.flush());
+                    lines.into_iter()
+                }
+                None => self.stream.take().unwrap().finish(),
+            };
+        }
+    }
+}
+
+impl<I: Iterator> iter::FusedIterator for Live<I> where I::Item: AsRef<[u8]> {}
+
+/// State for dumping consecutive buffers of a stream one at a time.
+///
+/// Each dump shows the offsets of its bytes within the stream, so a buffer
+/// following one that ended in a partial line continues at the offset
+/// after it. Created by `Config::continuation`.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+///
+/// let mut continuation = hexdump::Config::new().continuation();
+/// continuation.hexdump_iter(b"first packet").for_each(drop);
+/// let second: Vec<_> = continuation.hexdump_iter(b"second").collect();
+/// assert!(second[0].ends_with(" 0000000c"));
+/// assert!(second[1].ends_with(" 00000012"));
+/// assert_eq!(continuation.offset(), 18);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Continuation {
+    config: Config,
+}
+
+impl Continuation {
+    pub(crate) fn new(config: &Config) -> Continuation {
+        Continuation { config: config.clone() }
+    }
+    /// Returns the offset of the next buffer within the stream.
+    pub fn offset(&self) -> usize {
+        self.config.base_offset
+    }
+    /// Creates a hexdump iterator for the next buffer of the stream.
+    ///
+    /// Panics if the offsets overflow, see `Config::base_offset`.
+    pub fn hexdump_iter<'a>(&mut self, bytes: &'a [u8]) -> Hexdump<'a> {
+        let hexdump = Hexdump::new(bytes, self.config.clone());
+        self.config.base_offset = self.config.end_offset(bytes.len());
+        hexdump
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::HexdumpStream;
+    use super::hexdump_live_iter;
+    use config::Config;
+    use imp::hexdump_iter;
+
+    use std::cmp;
+
+    quickcheck! {
+        fn stream(bytes: Vec<u8>, splits: Vec<usize>) -> bool {
+            let mut stream = HexdumpStream::new();
+            let mut lines = Vec::new();
+            let mut rest = &bytes[..];
+            for split in splits {
+                let (piece, tail) = rest.split_at(cmp::min(split % 40, rest.len()));
+                lines.extend(stream.push(piece));
+                rest = tail;
+            }
+            lines.extend(stream.push(rest));
+            lines.extend(stream.finish());
+            lines == hexdump_iter(&bytes).collect::<Vec<_>>()
+        }
+    }
+
+    #[test]
+    fn test_live() {
+        let config = Config::new().bytes_per_line(4);
+        let buffers: Vec<&[u8]> = vec![b"abcdef", b"", b"gh", b"ijklm"];
+        let lines: Vec<_> = config.hexdump_live_iter(buffers.clone()).collect();
+        let mut expected = Vec::new();
+        let mut continuation = config.continuation();
+        for buffer in &buffers {
+            let mut dump: Vec<_> = continuation.hexdump_iter(buffer).collect();
+            dump.pop();
+            expected.extend(dump);
+        }
+        expected.extend(config.hexdump_range_iter(b"abcdefghijklm", 13..13));
+        assert_eq!(lines, expected);
+        assert_eq!(lines[2].offset(), Some(6));
+        let empty: Vec<_> = hexdump_live_iter(Vec::<Vec<u8>>::new()).collect();
+        assert_eq!(empty, hexdump_iter(b"").collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,244 @@
+use config::Config;
+use imp::Buffer;
+use imp::Line;
+use imp::write_offset;
+use layout::Endian;
+use std::fmt::Write;
+use std::iter;
+use std::ops;
+
+/// Return type of `hexdump_strings_iter`.
+///
+/// Yields a line for each run of printable characters in the input, like
+/// the `strings` tool: the offset of the string, a colon and the string in
+/// double quotes, with quotes, backslashes and tabs escaped like `\"`,
+/// `\\` and `\t`. Printable characters are those of printable ASCII and
+/// tabs. Strings found as UTF-16 have a `u` in front of the opening quote.
+#[derive(Clone, Debug)]
+pub struct Strings<'a> {
+    bytes: &'a [u8],
+    config: Config,
+    min_len: usize,
+    utf16: Option<Endian>,
+    position: usize,
+    ascii: Scan,
+    wide: Scan,
+}
+
+/// State of the search for the next string of one encoding.
+#[derive(Clone, Debug)]
+enum Scan {
+    /// The next string hasn't been searched for yet.
+    Pending,
+    /// The next string, which may overlap with the last one yielded.
+    Found(ops::Range<usize>),
+    /// There are no more strings.
+    Done,
+}
+
+/// Creates an iterator over the strings of at least `min_len` printable
+/// ASCII characters in the given bytes, like the `strings` tool.
+///
+/// See `Config::hexdump_strings_iter` for details.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+/// use hexdump::Endian;
+///
+/// let bytes = b"\x7fELF\x02\x01/lib64/ld.so\x00\x01w\x00i\x00d\x00e\x00";
+/// let lines: Vec<_> = hexdump::hexdump_strings_iter(bytes, 4)
+///     .utf16(Endian::Little)
+///     .map(|l| l.to_string())
+///     .collect();
+/// assert_eq!(lines, [
+///     "00000006: \"/lib64/ld.so\"",
+///     "00000014: u\"wide\"",
+/// ]);
+/// ```
+pub fn hexdump_strings_iter(bytes: &[u8], min_len: usize) -> Strings<'_> {
+    Config::new().hexdump_strings_iter(bytes, min_len)
+}
+
+impl<'a> Strings<'a> {
+    pub(crate) fn new(bytes: &'a [u8], config: &Config, min_len: usize) -> Strings<'a> {
+        assert!(min_len != 0, "minimum string length must be positive");
+        Strings {
+            bytes,
+            config: config.clone(),
+            min_len,
+            utf16: None,
+            position: 0,
+            ascii: Scan::Pending,
+            wide: Scan::Done,
+        }
+    }
+    /// Also yields strings of at least `min_len` UTF-16 code units of
+    /// printable ASCII characters with the given byte order, as used e.g.
+    /// by Windows and Java.
+    ///
+    /// The strings are found at both even and odd offsets.
+    ///
+    /// # Panics
+    ///
+    /// Panics if iteration has already started.
+    pub fn utf16(mut self, endian: Endian) -> Strings<'a> {
+        assert!(self.position == 0 && matches!(self.ascii, Scan::Pending),
+                "UTF-16 must be enabled before iterating");
+        self.utf16 = Some(endian);
+        self.wide = Scan::Pending;
+        self
+    }
+    fn is_printable(&self, position: usize) -> bool {
+        matches!(self.bytes[position], 0x20..=0x7e | b'\t') && !self.config.is_redacted(position)
+    }
+    fn is_utf16_printable(&self, position: usize, endian: Endian) -> bool {
+        let (low, high) = match endian {
+            Endian::Little => (position, position + 1),
+            Endian::Big => (position + 1, position),
+        };
+        self.bytes[high] == 0 && !self.config.is_redacted(high) && self.is_printable(low)
+    }
+    /// Returns the first string of ASCII characters from `from` on.
+    fn find_ascii(&self, from: usize) -> Option<ops::Range<usize>> {
+        let mut start = from;
+        for i in from..self.bytes.len() + 1 {
+            if i < self.bytes.len() && self.is_printable(i) {
+                continue;
+            }
+            if i - start >= self.min_len {
+                return Some(start..i);
+            }
+            start = i + 1;
+        }
+        None
+    }
+    /// Returns the first string of UTF-16 code units starting at `from`
+    /// plus a multiple of two.
+    fn find_utf16_aligned(&self, from: usize, endian: Endian) -> Option<ops::Range<usize>> {
+        let mut start = from;
+        let mut i = from;
+        while i + 2 <= self.bytes.len() {
+            if !self.is_utf16_printable(i, endian) {
+                if (i - start) / 2 >= self.min_len {
+                    return Some(start..i);
+                }
+                start = i + 2;
+            }
+            i += 2;
+        }
+        if (i - start) / 2 >= self.min_len { Some(start..i) } else { None }
+    }
+    /// Returns the next string of the given encoding, searching for it if
+    /// it isn't known yet or overlaps with the last one yielded.
+    fn peek(&mut self, wide: bool) -> Option<ops::Range<usize>> {
+        let scan = if wide { &self.wide } else { &self.ascii };
+        let stale = match *scan {
+            Scan::Pending => true,
+            Scan::Found(ref range) => range.start < self.position,
+            Scan::Done => false,
+        };
+        if stale {
+            let found = match self.utf16 {
+                Some(endian) if wide => {
+                    let even = self.find_utf16_aligned(self.position, endian);
+                    let odd = self.find_utf16_aligned(self.position + 1, endian);
+                    even.into_iter().chain(odd).min_by_key(|range| range.start)
+                }
+                _ => self.find_ascii(self.position),
+            };
+            let scan = found.map_or(Scan::Done, Scan::Found);
+            if wide {
+                self.wide = scan;
+            } else {
+                self.ascii = scan;
+            }
+        }
+        match *(if wide { &self.wide } else { &self.ascii }) {
+            Scan::Found(ref range) => Some(range.clone()),
+            _ => None,
+        }
+    }
+    fn line(&self, range: ops::Range<usize>, wide: bool) -> Line {
+        let mut buf = Buffer::new();
+        write_offset(&mut buf, &self.config, self.config.end_offset(range.start));
+        buf.write_str(if wide { ": u\"" } else { ": \"" }).unwrap();
+        let step = if wide { 2 } else { 1 };
+        for i in range.step_by(step) {
+            let low = match self.utf16 {
+                Some(Endian::Big) if wide => i + 1,
+                _ => i,
+            };
+            match self.bytes[low] {
+                b'"' => buf.write_str("\\\""),
+                b'\\' => buf.write_str("\\\\"),
+                b'\t' => buf.write_str("\\t"),
+                b => buf.write_char(b as char),
+            }.unwrap();
+        }
+        buf.write_str("\"").unwrap();
+        Line::new(buf)
+    }
+}
+
+impl<'a> Iterator for Strings<'a> {
+    type Item = Line;
+    fn next(&mut self) -> Option<Line> {
+        let ascii = self.peek(false);
+        let wide = self.peek(true);
+        let (range, wide) = match (ascii, wide) {
+            (Some(ascii), Some(wide)) if wide.start < ascii.start => (wide, true),
+            (Some(ascii), _) => (ascii, false),
+            (None, Some(wide)) => (wide, true),
+            (None, None) => return None,
+        };
+        self.position = range.end;
+        Some(self.line(range, wide))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some((self.bytes.len() - self.position) / self.min_len))
+    }
+}
+
+impl<'a> iter::FusedIterator for Strings<'a> {}
+
+#[cfg(test)]
+mod test {
+    use config::Config;
+    use layout::Endian;
+
+    #[test]
+    fn test_strings() {
+        let strings = |config: Config, bytes: &[u8], utf16: Option<Endian>| -> Vec<_> {
+            let iter = config.hexdump_strings_iter(bytes, 3);
+            let iter = match utf16 {
+                Some(endian) => iter.utf16(endian),
+                None => iter,
+            };
+            iter.map(|l| l.to_string()).collect()
+        };
+        let bytes = b"ab\x00say \"hi\"\t\\\xffsecret\x01abc";
+        assert_eq!(strings(Config::new(), bytes, None), [
+            "00000003: \"say \\\"hi\\\"\\t\\\\\"",
+            "0000000e: \"secret\"",
+            "00000015: \"abc\"",
+        ]);
+        assert_eq!(strings(Config::new().redact(0x10..0x11).base_offset(0x100), bytes, None), [
+            "00000103: \"say \\\"hi\\\"\\t\\\\\"",
+            "00000111: \"ret\"",
+            "00000115: \"abc\"",
+        ]);
+        // An ASCII string running into a UTF-16 string at an odd offset.
+        let bytes = b"xyz\x00\x01abcd\x00e\x00f\x00g\x00\x01";
+        assert_eq!(strings(Config::new(), bytes, Some(Endian::Little)), [
+            "00000000: \"xyz\"",
+            "00000005: \"abcd\"",
+            "0000000a: u\"efg\"",
+        ]);
+        assert_eq!(strings(Config::new(), b"\x00a\x00b\x00c", Some(Endian::Big)), [
+            "00000000: u\"abc\"",
+        ]);
+        assert!(strings(Config::new(), b"ab\x00cd", Some(Endian::Little)).is_empty());
+    }
+}
@@ -0,0 +1,266 @@
+use highlight::Highlight;
+use std::env;
+use std::fmt;
+use std::sync::Arc;
+
+/// Terminal color used by a `Style`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Color {
+    /// ANSI black.
+    Black,
+    /// ANSI red.
+    Red,
+    /// ANSI green.
+    Green,
+    /// ANSI yellow.
+    Yellow,
+    /// ANSI blue.
+    Blue,
+    /// ANSI magenta.
+    Magenta,
+    /// ANSI cyan.
+    Cyan,
+    /// ANSI white.
+    White,
+    /// 24-bit color given by its red, green and blue components.
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    fn write<W: fmt::Write>(self, w: &mut W, base: u8) -> fmt::Result {
+        let index = match self {
+            Color::Black => 0,
+            Color::Red => 1,
+            Color::Green => 2,
+            Color::Yellow => 3,
+            Color::Blue => 4,
+            Color::Magenta => 5,
+            Color::Cyan => 6,
+            Color::White => 7,
+            Color::Rgb(r, g, b) => return write!(w, "{};2;{};{};{}", base + 8, r, g, b),
+        };
+        write!(w, "{}", base + index)
+    }
+}
+
+/// Appearance of a byte, rendered as ANSI escape sequences.
+///
+/// Returned by the callback passed to `Config::style`.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+/// use hexdump::Color;
+/// use hexdump::Style;
+///
+/// let consumed = 6;
+/// hexdump::Config::new()
+///     .style(move |offset, _| if offset < consumed {
+///         Style::new().foreground(Color::Green)
+///     } else {
+///         Style::new().foreground(Color::Red).underline()
+///     })
+///     .hexdump(b"\x00\x04abcdleftover");
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Style {
+    foreground: Option<Color>,
+    background: Option<Color>,
+    bold: bool,
+    underline: bool,
+    reverse: bool,
+}
+
+impl Style {
+    /// Creates a style that leaves the byte unchanged.
+    pub fn new() -> Style {
+        Style::default()
+    }
+    /// Sets the foreground color.
+    pub fn foreground(mut self, color: Color) -> Style {
+        self.foreground = Some(color);
+        self
+    }
+    /// Sets the background color.
+    pub fn background(mut self, color: Color) -> Style {
+        self.background = Some(color);
+        self
+    }
+    /// Shows the byte in bold.
+    pub fn bold(mut self) -> Style {
+        self.bold = true;
+        self
+    }
+    /// Underlines the byte.
+    pub fn underline(mut self) -> Style {
+        self.underline = true;
+        self
+    }
+    /// Swaps foreground and background, which makes for a clear marker.
+    pub fn reverse(mut self) -> Style {
+        self.reverse = true;
+        self
+    }
+    /// Returns whether the style leaves the byte unchanged.
+    pub fn is_plain(&self) -> bool {
+        *self == Style::default()
+    }
+    /// Writes the escape sequence switching to this style.
+    pub(crate) fn write_start<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        if self.is_plain() {
+            return Ok(());
+        }
+        w.write_str("\x1b[")?;
+        let mut first = true;
+        let mut separator = |w: &mut W| {
+            if !first {
+                w.write_str(";")?;
+            }
+            first = false;
+            Ok(())
+        };
+        if let Some(color) = self.foreground {
+            separator(w)?;
+            color.write(w, 30)?;
+        }
+        if let Some(color) = self.background {
+            separator(w)?;
+            color.write(w, 40)?;
+        }
+        for &(set, code) in &[(self.bold, "1"), (self.underline, "4"), (self.reverse, "7")] {
+            if set {
+                separator(w)?;
+                w.write_str(code)?;
+            }
+        }
+        w.write_str("m")
+    }
+    /// Writes the escape sequence switching back from this style.
+    pub(crate) fn write_end<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        if self.is_plain() {
+            return Ok(());
+        }
+        w.write_str("\x1b[0m")
+    }
+}
+
+/// Palette of the built-in styles, see `Config::theme`.
+///
+/// The default theme uses the basic ANSI colors, which terminals map to
+/// their own palette.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+/// use hexdump::Color;
+/// use hexdump::Style;
+/// use hexdump::Theme;
+///
+/// let theme = Theme {
+///     removed: Style::new().foreground(Color::Rgb(0xd7, 0x5f, 0x5f)),
+///     added: Style::new().foreground(Color::Rgb(0x5f, 0xaf, 0x5f)),
+///     ..Theme::default()
+/// };
+/// hexdump::Config::new().theme(theme).dump_diff(b"abc", b"abd");
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Theme {
+    /// Style of `Highlight::NulRuns`.
+    pub nul_runs: Style,
+    /// Style of `Highlight::FfRuns`.
+    pub ff_runs: Style,
+    /// Style of `Highlight::NonAscii`.
+    pub non_ascii: Style,
+    /// Style of `Highlight::Utf8Errors`.
+    pub utf8_errors: Style,
+    /// Style of `Highlight::Aligned`.
+    pub aligned: Style,
+    /// Style of the differing bytes of the old buffer in a `DumpDiff`.
+    pub removed: Style,
+    /// Style of the differing bytes of the new buffer in a `DumpDiff`.
+    pub added: Style,
+    /// Styles of the byte classes of an `Overview`: zero bytes, printable
+    /// ASCII, other control characters, bytes above 0x7f and 0xff bytes.
+    pub overview: [Style; 5],
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme {
+            nul_runs: Style::new().foreground(Color::Blue),
+            ff_runs: Style::new().foreground(Color::Magenta),
+            non_ascii: Style::new().foreground(Color::Yellow),
+            utf8_errors: Style::new().foreground(Color::Red).reverse(),
+            aligned: Style::new().underline(),
+            removed: Style::new().foreground(Color::Red).bold(),
+            added: Style::new().foreground(Color::Green).bold(),
+            overview: [
+                Style::new().foreground(Color::Black),
+                Style::new().foreground(Color::Blue),
+                Style::new().foreground(Color::Green),
+                Style::new().foreground(Color::Red),
+                Style::new().foreground(Color::White),
+            ],
+        }
+    }
+}
+
+impl Theme {
+    /// Returns the style of the given highlighting rule.
+    pub fn highlight(&self, rule: Highlight) -> Style {
+        match rule {
+            Highlight::NulRuns => self.nul_runs,
+            Highlight::FfRuns => self.ff_runs,
+            Highlight::NonAscii => self.non_ascii,
+            Highlight::Utf8Errors => self.utf8_errors,
+            Highlight::Aligned(_) => self.aligned,
+        }
+    }
+}
+
+/// Returns whether `ColorChoice::Auto` enables colors for a stream that is
+/// or isn't a terminal.
+///
+/// A set `CLICOLOR_FORCE` other than `0` enables colors regardless, a
+/// non-empty `NO_COLOR` disables them otherwise.
+pub(crate) fn colors_enabled(terminal: bool) -> bool {
+    let var = |name| env::var_os(name).filter(|value| !value.is_empty());
+    if var("CLICOLOR_FORCE").is_some_and(|value| value != "0") {
+        return true;
+    }
+    var("NO_COLOR").is_none() && terminal
+}
+
+/// Callback set by `Config::style`.
+#[derive(Clone)]
+pub(crate) struct StyleFn(pub(crate) Arc<dyn Fn(usize, u8) -> Style + Send + Sync>);
+
+impl fmt::Debug for StyleFn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("StyleFn(..)")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Color;
+    use super::Style;
+    use config::ColorChoice;
+    use config::Config;
+
+    #[test]
+    fn test_style() {
+        let config = Config::new().color(ColorChoice::Always).redact(3..4).style(|offset, byte| match offset {
+            0 => Style::new().foreground(Color::Red).bold(),
+            1 => Style::new().background(Color::Rgb(1, 2, 3)).underline().reverse(),
+            _ if byte == b'c' => Style::new(),
+            _ => Style::new().foreground(Color::Blue),
+        });
+        let line = config.hexdump_iter(b"abcd").next().unwrap();
+        assert_eq!(line.to_string(), format!("\
+            |\x1b[31;1m61\x1b[0m\x1b[48;2;1;2;3;4;7m62\x1b[0m63xx|{:28}\
+            \x1b[31;1ma\x1b[0m\x1b[48;2;1;2;3;4;7mb\x1b[0mc*{:13}00000000", "", ""));
+    }
+}
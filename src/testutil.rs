@@ -0,0 +1,14 @@
+use std::io;
+
+/// Writer failing like a pipe whose reading end was closed.
+#[derive(Debug)]
+pub(crate) struct Closed;
+
+impl io::Write for Closed {
+    fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+        Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
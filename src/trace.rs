@@ -0,0 +1,246 @@
+use config::Config;
+use imp::Hexdump;
+use imp::Line;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use writer::write_lines;
+
+/// Direction of a message in a `Trace`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Direction {
+    /// Sent data, marked with `>>`.
+    Tx,
+    /// Received data, marked with `<<`.
+    Rx,
+}
+
+impl Direction {
+    /// Returns the marker shown in front of the lines of a message.
+    pub fn marker(self) -> &'static str {
+        match self {
+            Direction::Tx => ">>",
+            Direction::Rx => "<<",
+        }
+    }
+}
+
+/// Protocol trace tagging each dumped message with a sequence number and
+/// its direction.
+///
+/// Each line of a message is prefixed with the six-digit sequence number of
+/// the message and the marker of its direction, e.g. `000003 >> `. Clones
+/// of a trace share the sequence counter, so messages traced through
+/// different clones, possibly on different threads, are numbered in the
+/// order they were dumped. Created by `Config::trace`.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+/// use hexdump::Direction;
+///
+/// let trace = hexdump::Config::new().trace();
+/// for line in trace.hexdump_iter(Direction::Tx, b"PING") {
+///     println!("{}", line);
+/// }
+/// let reply: Vec<_> = trace.hexdump_iter(Direction::Rx, b"PONG").collect();
+/// assert!(reply[0].starts_with("000001 << |504f4e47|"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Trace {
+    config: Config,
+    sequence: Arc<AtomicUsize>,
+}
+
+/// Return type of `Trace::hexdump_iter`.
+pub struct TraceLines<'a> {
+    inner: Hexdump<'a>,
+    prefix: String,
+}
+
+impl Trace {
+    pub(crate) fn new(config: &Config) -> Trace {
+        Trace {
+            config: config.clone(),
+            sequence: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+    fn prefix(&self, direction: Direction) -> String {
+        let sequence = self.sequence.fetch_add(1, Ordering::Relaxed);
+        format!("{:06} {} ", sequence, direction.marker())
+    }
+    /// Creates an iterator over the tagged lines of a dump of one message.
+    ///
+    /// The message takes the next sequence number.
+    pub fn hexdump_iter<'a>(&self, direction: Direction, bytes: &'a [u8]) -> TraceLines<'a> {
+        TraceLines {
+            inner: Hexdump::new(bytes, self.config.clone()),
+            prefix: self.prefix(direction),
+        }
+    }
+    /// Wraps a connection, writing a tagged dump of every read and every
+    /// write to `sink`.
+    pub fn wrap<T, S: Write>(&self, inner: T, sink: S) -> Traced<T, S> {
+        Traced {
+            inner,
+            sink,
+            trace: self.clone(),
+            rx_offset: 0,
+            tx_offset: 0,
+            error: None,
+        }
+    }
+}
+
+impl<'a> Iterator for TraceLines<'a> {
+    type Item = Line;
+    fn next(&mut self) -> Option<Line> {
+        self.inner.next().map(|line| line.prefixed(&self.prefix))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for TraceLines<'a> {}
+
+/// Connection wrapped by `Trace::wrap`.
+///
+/// Every successful read is dumped as a received message and every
+/// successful write as a sent one. The offsets of each direction continue
+/// from one message to the next.
+///
+/// Failing to write the trace doesn't fail the read or write, which
+/// already happened. Instead, the trace skips messages until the error is
+/// returned by the next `flush` or `take_error`. Each message is written
+/// as a whole, so nothing is left to write when the connection is dropped.
+#[derive(Debug)]
+pub struct Traced<T, S> {
+    inner: T,
+    sink: S,
+    trace: Trace,
+    rx_offset: usize,
+    tx_offset: usize,
+    error: Option<io::Error>,
+}
+
+impl<T, S: Write> Traced<T, S> {
+    /// Returns a reference to the wrapped connection.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+    /// Returns a mutable reference to the wrapped connection.
+    ///
+    /// Data transferred directly through it doesn't show up in the trace.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+    /// Returns the wrapped connection and the sink.
+    pub fn into_inner(self) -> (T, S) {
+        (self.inner, self.sink)
+    }
+    /// Returns the first error writing the trace since the last call, if
+    /// any.
+    pub fn take_error(&mut self) -> Option<io::Error> {
+        self.error.take()
+    }
+    /// Dumps a message, keeping an error for later.
+    fn dump(&mut self, direction: Direction, bytes: &[u8]) {
+        let offset = match direction {
+            Direction::Tx => &mut self.tx_offset,
+            Direction::Rx => &mut self.rx_offset,
+        };
+        let config = self.trace.config.clone().base_offset(*offset);
+        *offset += bytes.len();
+        let lines = TraceLines {
+            inner: Hexdump::new(bytes, config.clone()),
+            prefix: self.trace.prefix(direction),
+        };
+        if self.error.is_none() {
+            self.error = write_lines(&mut self.sink, &config, lines).err();
+        }
+    }
+}
+
+impl<T: Read, S: Write> Read for Traced<T, S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        if read != 0 {
+            self.dump(Direction::Rx, &buf[..read]);
+        }
+        Ok(read)
+    }
+}
+
+impl<T: Write, S: Write> Write for Traced<T, S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        if written != 0 {
+            self.dump(Direction::Tx, &buf[..written]);
+        }
+        Ok(written)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()?;
+        match self.error.take() {
+            Some(e) => Err(e),
+            None => self.sink.flush(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use config::Config;
+    use testutil::Closed;
+
+    use std::io::Read;
+    use std::io::Write;
+    use std::io;
+
+    #[test]
+    fn test_trace() {
+        struct Connection(&'static [u8], Vec<u8>);
+        impl io::Read for Connection {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                self.0.read(buf)
+            }
+        }
+        impl io::Write for Connection {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.1.write(buf)
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+        let mut log = Vec::new();
+        {
+            let trace = Config::new().trace();
+            let mut connection = trace.wrap(Connection(b"+OK\r\n", Vec::new()), &mut log);
+            connection.write_all(b"PING").unwrap();
+            let mut reply = [0; 5];
+            connection.read_exact(&mut reply).unwrap();
+            connection.write_all(b"QUIT").unwrap();
+        }
+        assert_eq!(String::from_utf8(log).unwrap(), "\
+            000000 >> |50494e47|                            PING             00000000\n\
+            000000 >>                                                        00000004\n\
+            000001 << |2b4f4b0d 0a|                         +OK..            00000000\n\
+            000001 <<                                                        00000005\n\
+            000002 >> |51554954|                            QUIT             00000004\n\
+            000002 >>                                                        00000008\n");
+
+        let mut connection = Config::new().trace().wrap(Connection(b"+OK\r\n", Vec::new()), Closed);
+        assert_eq!(connection.write(b"PING").unwrap(), 4);
+        assert_eq!(connection.flush().unwrap_err().kind(), io::ErrorKind::BrokenPipe);
+        assert_eq!(connection.read(&mut [0; 5]).unwrap(), 5);
+        assert_eq!(connection.take_error().unwrap().kind(), io::ErrorKind::BrokenPipe);
+        assert!(connection.take_error().is_none());
+        assert_eq!(connection.get_ref().1, b"PING");
+    }
+}
@@ -0,0 +1,20 @@
+//! JavaScript bindings, enabled by the `wasm` feature.
+//!
+//! The functions take a `Uint8Array` and never write to stdout.
+
+use config::Config;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// Returns the lines of the hexdump of the given bytes as an array of
+/// strings.
+#[wasm_bindgen(js_name = hexdumpLines)]
+pub fn hexdump_lines(bytes: &[u8]) -> Vec<String> {
+    Config::new().hexdump_iter(bytes).map(|l| l.to_string()).collect()
+}
+
+/// Returns the hexdump of the given bytes as a single string, each line
+/// terminated by `"\n"`.
+#[wasm_bindgen(js_name = hexdumpString)]
+pub fn hexdump_string(bytes: &[u8]) -> String {
+    Config::new().hexdump_string(bytes)
+}
@@ -0,0 +1,119 @@
+use config::Config;
+use imp::Buffer;
+use imp::Line;
+use imp::cell_width;
+use imp::data_column;
+use imp::format_line;
+use std::cmp;
+use std::fmt::Write;
+
+/// Return type of `hexdump_watch`.
+///
+/// Keeps the previous capture of a region, like device registers or shared
+/// memory polled in a loop, and formats only the lines that changed since
+/// then.
+#[derive(Clone, Debug)]
+pub struct Watch {
+    config: Config,
+    previous: Option<Vec<u8>>,
+}
+
+/// Creates a watch over a region that is captured repeatedly.
+///
+/// See `Config::hexdump_watch` for details.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+///
+/// let mut watch = hexdump::hexdump_watch();
+/// assert_eq!(watch.capture(b"abcd").len(), 1);
+/// assert!(watch.capture(b"abcd").is_empty());
+/// let lines = watch.capture(b"abXd");
+/// assert_eq!(lines[0].to_string(), "|61625864|                            abXd             00000000");
+/// assert_eq!(lines[1].to_string(), "     ^^");
+/// ```
+pub fn hexdump_watch() -> Watch {
+    Config::new().hexdump_watch()
+}
+
+impl Watch {
+    pub(crate) fn new(config: &Config) -> Watch {
+        Watch { config: config.clone(), previous: None }
+    }
+    /// Compares `bytes` with the previous capture and returns the lines
+    /// that differ, each followed by a line marking its changed bytes.
+    ///
+    /// The first capture, and the first one after `reset`, returns all
+    /// lines without markers. Bytes past the end of the previous capture
+    /// count as changed.
+    pub fn capture(&mut self, bytes: &[u8]) -> Vec<Line> {
+        let chunk_length = self.config.chunk_length();
+        let mut result = Vec::new();
+        for (index, chunk) in bytes.chunks(chunk_length).enumerate() {
+            let offset = index * chunk_length;
+            let previous = self.previous.as_ref().map(|p| {
+                &p[cmp::min(offset, p.len())..cmp::min(offset + chunk_length, p.len())]
+            });
+            if previous == Some(chunk) {
+                continue;
+            }
+            result.push(format_line(&self.config, offset, chunk));
+            if let Some(previous) = previous {
+                result.push(self.marker(chunk, previous));
+            }
+        }
+        self.previous = Some(bytes.to_vec());
+        result
+    }
+    /// Forgets the previous capture, so the next one is shown in full.
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+    /// Formats a line marking the bytes of `chunk` that differ from
+    /// `previous`.
+    fn marker(&self, chunk: &[u8], previous: &[u8]) -> Line {
+        let width = cell_width(&self.config);
+        let mut buf = Buffer::new();
+        let mut column = 0;
+        for (i, &b) in chunk.iter().enumerate() {
+            if previous.get(i) == Some(&b) {
+                continue;
+            }
+            for _ in column..data_column(&self.config, i) {
+                buf.write_str(" ").unwrap();
+            }
+            for _ in 0..width {
+                buf.write_str("^").unwrap();
+            }
+            column = data_column(&self.config, i) + width;
+        }
+        Line::new(buf)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use config::Config;
+
+    #[test]
+    fn test_watch() {
+        let mut watch = Config::new().hexdump_watch();
+        let mut bytes: Vec<u8> = (0..40).collect();
+        assert_eq!(watch.capture(&bytes).len(), 3);
+        assert!(watch.capture(&bytes).is_empty());
+        bytes[17] = 0xff;
+        bytes[20] = 0xff;
+        bytes.push(40);
+        let lines: Vec<_> = watch.capture(&bytes).iter().map(|l| l.to_string()).collect();
+        assert_eq!(lines, [
+            "|10ff1213 ff151617 18191a1b 1c1d1e1f| ................ 00000010",
+            "   ^^     ^^",
+            "|20212223 24252627 28|                 !\"#$%&'(        00000020",
+            "                   ^^",
+        ]);
+        watch.reset();
+        assert_eq!(watch.capture(&bytes).len(), 3);
+    }
+}
@@ -0,0 +1,156 @@
+use config::Config;
+use imp::Buffer;
+use imp::Line;
+use std::cmp;
+use std::fmt::Write;
+use std::iter;
+use trace::Direction;
+
+/// Return type of `hexdump_wireshark_iter`.
+///
+/// Yields the lines of one packet in the format accepted by Wireshark's
+/// "Import from Hex Dump" and `text2pcap`: the offset within the packet as
+/// six or more hex digits, followed by the bytes as pairs of hex digits,
+/// all separated by single spaces. The text column is left out since
+/// `text2pcap` may mistake text looking like hex digits for bytes.
+///
+/// Dumps of consecutive packets can be concatenated, each one starting at
+/// offset zero.
+#[derive(Clone, Debug)]
+pub struct Wireshark<'a> {
+    bytes: &'a [u8],
+    chunk_length: usize,
+    uppercase: bool,
+    direction: Option<Direction>,
+    front: usize,
+    back: usize,
+}
+
+/// Creates an iterator over the lines of a packet in the format of
+/// Wireshark's "Import from Hex Dump".
+///
+/// See `Config::hexdump_wireshark_iter` for details.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+/// use hexdump::Direction;
+///
+/// let packet: Vec<u8> = (0..20).collect();
+/// let lines: Vec<_> = hexdump::hexdump_wireshark_iter(&packet)
+///     .direction(Direction::Rx)
+///     .map(|l| l.to_string())
+///     .collect();
+/// assert_eq!(lines, [
+///     "I",
+///     "000000 00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f",
+///     "000010 10 11 12 13",
+/// ]);
+/// ```
+pub fn hexdump_wireshark_iter(bytes: &[u8]) -> Wireshark<'_> {
+    Config::new().hexdump_wireshark_iter(bytes)
+}
+
+impl<'a> Wireshark<'a> {
+    pub(crate) fn new(bytes: &'a [u8], config: &Config) -> Wireshark<'a> {
+        let chunk_length = config.chunk_length();
+        Wireshark {
+            bytes,
+            chunk_length,
+            uppercase: config.uppercase,
+            direction: None,
+            front: 0,
+            back: bytes.len().div_ceil(chunk_length),
+        }
+    }
+    /// Precedes the packet with a line indicating its direction, `I` for
+    /// received and `O` for sent packets, as expected by `text2pcap -D`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if iteration has already started.
+    pub fn direction(mut self, direction: Direction) -> Wireshark<'a> {
+        assert!(self.front == 0 && self.direction.is_none(),
+                "direction must be set before iterating");
+        self.direction = Some(direction);
+        self.back += 1;
+        self
+    }
+    fn line(&self, mut index: usize) -> Line {
+        let mut buf = Buffer::new();
+        if let Some(direction) = self.direction {
+            if index == 0 {
+                buf.write_str(match direction {
+                    Direction::Rx => "I",
+                    Direction::Tx => "O",
+                }).unwrap();
+                return Line::new(buf);
+            }
+            index -= 1;
+        }
+        let start = index * self.chunk_length;
+        let end = cmp::min(start + self.chunk_length, self.bytes.len());
+        write!(buf, "{:06x}", start).unwrap();
+        for &b in &self.bytes[start..end] {
+            if self.uppercase {
+                write!(buf, " {:02X}", b).unwrap();
+            } else {
+                write!(buf, " {:02x}", b).unwrap();
+            }
+        }
+        Line::new(buf)
+    }
+}
+
+impl<'a> Iterator for Wireshark<'a> {
+    type Item = Line;
+    fn next(&mut self) -> Option<Line> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.line(self.front - 1))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
+}
+
+impl<'a> DoubleEndedIterator for Wireshark<'a> {
+    fn next_back(&mut self) -> Option<Line> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.line(self.back))
+    }
+}
+
+impl<'a> iter::FusedIterator for Wireshark<'a> {}
+
+impl<'a> ExactSizeIterator for Wireshark<'a> {
+    fn len(&self) -> usize {
+        self.back - self.front
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use config::Config;
+
+    #[test]
+    fn test_wireshark() {
+        use trace::Direction;
+        let config = Config::new().bytes_per_line(8).uppercase(true);
+        let lines: Vec<_> = config.hexdump_wireshark_iter(b"\xab\xcdefghijk")
+            .direction(Direction::Tx)
+            .map(|l| l.to_string())
+            .collect();
+        assert_eq!(lines, ["O", "000000 AB CD 65 66 67 68 69 6A", "000008 6B"]);
+        assert_eq!(config.hexdump_wireshark_iter(b"").count(), 0);
+        let long = vec![0; 0x1000010];
+        assert_eq!(config.hexdump_wireshark_iter(&long).next_back().unwrap().to_string(),
+                   "1000008 00 00 00 00 00 00 00 00");
+    }
+}
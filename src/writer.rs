@@ -0,0 +1,211 @@
+use config::Config;
+use imp::Line;
+use std::io;
+use std::io::Write;
+use std::mem;
+use std::thread;
+use stream::HexdumpStream;
+
+/// Writer that forwards everything to an inner writer while writing a
+/// hexdump of the bytes to a sink.
+///
+/// The dump is written in complete lines with the offsets of the bytes in
+/// the whole stream, see `HexdumpStream`. The remaining bytes of a partial
+/// line and the summary line are only written by `finish`, or when the
+/// writer is dropped. Created by `Config::hexdump_writer`.
+///
+/// Failing to write the dump doesn't fail the write to the inner writer,
+/// which already happened. Instead, the dump skips lines until the error
+/// is returned by the next `flush` or by `finish`. Errors while writing the
+/// rest of the dump on drop are ignored, call `finish` to handle them.
+///
+/// # Example
+///
+/// ```
+/// extern crate hexdump;
+/// use std::io::Write;
+///
+/// let mut dump = Vec::new();
+/// let mut writer = hexdump::Config::new().hexdump_writer(Vec::new(), &mut dump);
+/// writer.write_all(b"GET / HTTP/1.1\r\n").unwrap();
+/// writer.write_all(b"\r\n").unwrap();
+/// let (sent, _) = writer.finish().unwrap();
+/// assert_eq!(sent, b"GET / HTTP/1.1\r\n\r\n");
+/// assert_eq!(dump, hexdump::hexdump_string(&sent).into_bytes());
+/// ```
+#[derive(Debug)]
+pub struct HexdumpWriter<W, S: Write> {
+    inner: W,
+    dump: SinkDump<S>,
+}
+
+/// Dump of the bytes passing through a `HexdumpWriter` or a
+/// `HexdumpReader`, written to their sink.
+///
+/// Keeps the first error writing to the sink until `flush` or `finish`,
+/// and writes the rest of the dump when dropped.
+#[derive(Debug)]
+pub(crate) struct SinkDump<S: Write> {
+    /// Only `None` after `finish`.
+    sink: Option<S>,
+    config: Config,
+    stream: HexdumpStream,
+    error: Option<io::Error>,
+}
+
+pub(crate) fn write_lines<S, I>(sink: &mut S, config: &Config, lines: I) -> io::Result<()>
+    where S: Write, I: Iterator<Item=Line>
+{
+    let terminator = config.line_terminator.as_str();
+    for line in lines {
+        sink.write_all(line.as_bytes())?;
+        sink.write_all(terminator.as_bytes())?;
+    }
+    Ok(())
+}
+
+impl<S: Write> SinkDump<S> {
+    pub(crate) fn new(config: &Config, sink: S) -> SinkDump<S> {
+        SinkDump {
+            sink: Some(sink),
+            config: config.clone(),
+            stream: config.hexdump_stream(),
+            error: None,
+        }
+    }
+    /// Returns the number of bytes dumped so far.
+    pub(crate) fn offset(&self) -> usize {
+        self.stream.len()
+    }
+    /// Dumps bytes that were passed through, keeping an error for later.
+    pub(crate) fn push(&mut self, bytes: &[u8]) {
+        let lines = self.stream.push(bytes);
+        if self.error.is_none() {
+            let sink = self.sink.as_mut().unwrap();
+            self.error = write_lines(sink, &self.config, lines).err();
+        }
+    }
+    /// Flushes the sink, or returns the kept error.
+    pub(crate) fn flush(&mut self) -> io::Result<()> {
+        match self.error.take() {
+            Some(e) => Err(e),
+            None => self.sink.as_mut().unwrap().flush(),
+        }
+    }
+    /// Writes the rest of the dump and returns the sink, or the kept error.
+    pub(crate) fn finish(mut self) -> io::Result<S> {
+        let mut sink = self.sink.take().unwrap();
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+        let stream = mem::take(&mut self.stream);
+        write_lines(&mut sink, &self.config, stream.finish())?;
+        sink.flush()?;
+        Ok(sink)
+    }
+}
+
+impl<S: Write> Drop for SinkDump<S> {
+    fn drop(&mut self) {
+        if let Some(ref mut sink) = self.sink {
+            if self.error.is_none() && !thread::panicking() {
+                let stream = mem::take(&mut self.stream);
+                let _ = write_lines(sink, &self.config, stream.finish());
+                let _ = sink.flush();
+            }
+        }
+    }
+}
+
+impl<W: Write, S: Write> HexdumpWriter<W, S> {
+    pub(crate) fn new(config: &Config, inner: W, sink: S) -> HexdumpWriter<W, S> {
+        HexdumpWriter {
+            inner,
+            dump: SinkDump::new(config, sink),
+        }
+    }
+    /// Returns a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+    /// Returns a mutable reference to the inner writer.
+    ///
+    /// Bytes written directly to it don't show up in the dump.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+    /// Returns the number of bytes written so far.
+    pub fn offset(&self) -> usize {
+        self.dump.offset()
+    }
+    /// Writes the rest of the dump to the sink and returns the inner
+    /// writer and the sink.
+    ///
+    /// Returns the first error writing the dump, if any.
+    pub fn finish(self) -> io::Result<(W, S)> {
+        let sink = self.dump.finish()?;
+        Ok((self.inner, sink))
+    }
+}
+
+impl<W: Write, S: Write> Write for HexdumpWriter<W, S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.dump.push(&buf[..written]);
+        Ok(written)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()?;
+        self.dump.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use config::Config;
+    use imp::hexdump_string;
+    use testutil::Closed;
+
+    use std::cmp;
+    use std::io::Write;
+    use std::io;
+
+    #[test]
+    fn test_hexdump_writer() {
+        struct Trickle(Vec<u8>);
+        impl io::Write for Trickle {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                let len = cmp::min(buf.len(), 3);
+                self.0.extend_from_slice(&buf[..len]);
+                Ok(len)
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+        let bytes: Vec<u8> = (0..40).collect();
+        let mut dump = Vec::new();
+        let mut writer = Config::new().hexdump_writer(Trickle(Vec::new()), &mut dump);
+        assert_eq!(writer.write(&bytes).unwrap(), 3);
+        writer.write_all(&bytes[3..]).unwrap();
+        assert_eq!(writer.offset(), 40);
+        let (inner, _) = writer.finish().unwrap();
+        assert_eq!(inner.0, bytes);
+        assert_eq!(dump, hexdump_string(&bytes).into_bytes());
+    }
+
+    #[test]
+    fn test_hexdump_writer_sink_error() {
+        let bytes: Vec<u8> = (0..40).collect();
+        let mut writer = Config::new().hexdump_writer(Vec::new(), Closed);
+        assert_eq!(writer.write(&bytes).unwrap(), 40);
+        assert_eq!(writer.get_ref(), &bytes);
+        assert_eq!(writer.flush().unwrap_err().kind(), io::ErrorKind::BrokenPipe);
+        writer.write_all(&bytes).unwrap();
+        assert_eq!(writer.finish().unwrap_err().kind(), io::ErrorKind::BrokenPipe);
+
+        let mut dump = Vec::new();
+        Config::new().hexdump_writer(Vec::new(), &mut dump).write_all(&bytes).unwrap();
+        assert_eq!(dump, hexdump_string(&bytes).into_bytes());
+    }
+}
@@ -0,0 +1,152 @@
+#![cfg(feature = "cli")]
+
+extern crate hexdump;
+
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::process;
+use std::process::Command;
+use std::process::Stdio;
+
+fn run(args: &[&str], input: &[u8]) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_hexdump"))
+        .args(args)
+        .env_remove("CLICOLOR_FORCE")
+        .env_remove("HEXDUMP_COLS")
+        .env_remove("HEXDUMP_GROUP")
+        .env_remove("HEXDUMP_UPPER")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn default() {
+    let input = b"12345\0\r\n\t .abcdef";
+    assert_eq!(run(&[], input), hexdump::hexdump_string(input));
+}
+
+#[test]
+fn options() {
+    let input: Vec<u8> = (0..100).collect();
+    let config = hexdump::Config::new()
+        .bytes_per_line(8)
+        .checksum(hexdump::Checksum::Crc32)
+        .head_tail(1, 1);
+    let expected = config.hexdump_range_string(&input, 0x10..0x40);
+    let args = ["-n", "8", "--checksum", "crc32", "--head-tail", "1", "1", "-s", "0x10", "-l", "48"];
+    assert_eq!(run(&args, &input), expected);
+    let expected = hexdump::hexdump_range_iter(&input, 1..100)
+        .map(|l| format!("{}\n", l))
+        .collect::<String>();
+    assert_eq!(run(&["-s", "1", "-l", "0xffffffffffffffff"], &input), expected);
+}
+
+#[test]
+fn search() {
+    let input = b"0123456789abcdef0123456789abcdef";
+    let lines: Vec<_> = hexdump::hexdump_search_iter(input, b"a", 0)
+        .map(|l| format!("{}\n", l))
+        .collect();
+    assert_eq!(run(&["--search", "61", "-C", "0"], input), lines.concat());
+    let lines: Vec<_> = hexdump::Config::new().base_offset(20)
+        .hexdump_search_iter(&input[20..], b"a", 0)
+        .map(|l| format!("{}\n", l))
+        .collect();
+    assert_eq!(run(&["-s", "20", "--search", "61", "-C", "0"], input), lines.concat());
+    assert!(lines[0].ends_with(" 00000014\n"));
+}
+
+#[test]
+fn diff() {
+    let other: Vec<u8> = (0..100).collect();
+    let mut input = other.clone();
+    input[42] = 0;
+    let path = env::temp_dir().join(format!("hexdump-cli-diff-{}", process::id()));
+    fs::write(&path, &other).unwrap();
+    let output = run(&["--diff", path.to_str().unwrap()], &input);
+    fs::remove_file(&path).unwrap();
+    let lines: Vec<_> = hexdump::hexdump_diff_iter(&input[..], &other[..])
+        .map(|l| format!("{}\n", l.unwrap()))
+        .collect();
+    assert_eq!(output, lines.concat());
+}
+
+#[test]
+fn tail() {
+    let input: Vec<u8> = (0..100).collect();
+    let lines: Vec<_> = hexdump::hexdump_tail_iter(&input, 30)
+        .map(|l| format!("{}\n", l))
+        .collect();
+    assert_eq!(run(&["--tail", "30"], &input), lines.concat());
+    let path = env::temp_dir().join(format!("hexdump-cli-tail-{}", process::id()));
+    fs::write(&path, &input).unwrap();
+    let output = run(&["--tail", "30", path.to_str().unwrap()], b"");
+    fs::remove_file(&path).unwrap();
+    assert_eq!(output, lines.concat());
+}
+
+#[test]
+fn plain() {
+    let input: Vec<u8> = (0..40).collect();
+    let plain = run(&["-p"], &input);
+    assert_eq!(plain, "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d\n\
+                       1e1f2021222324252627\n");
+    assert_eq!(run(&["-r"], b"48656c6c6f\n2c20776f726c64\n"), "Hello, world");
+}
+
+#[test]
+fn color() {
+    let input = b"\x00\xff";
+    let plain = hexdump::hexdump_string(input);
+    assert_eq!(run(&["--heatmap"], input), plain);
+    assert_eq!(run(&["--heatmap", "--color", "never"], input), plain);
+    let colored = hexdump::Config::new().heatmap(true).color(hexdump::ColorChoice::Always)
+        .hexdump_string(input);
+    assert_eq!(run(&["--heatmap", "--color", "always"], input), colored);
+}
+
+#[test]
+fn strings() {
+    let input = b"\x00\x01hello\x00hi\x00world\xff";
+    assert_eq!(run(&["--strings", "4"], input), "00000002: \"hello\"\n0000000b: \"world\"\n");
+    assert_eq!(run(&["--strings", "2", "-s", "8"], input),
+               "00000008: \"hi\"\n0000000b: \"world\"\n");
+}
+
+#[test]
+fn utf8() {
+    assert_eq!(run(&["--utf8"], "a€".as_bytes()), "\
+|61e282ac|                            a...             00000000
+   ^^^^^^ '€' U+20AC
+                                                       00000004
+");
+}
+
+#[test]
+fn line_numbers() {
+    let input: Vec<u8> = (0..40).collect();
+    let output = run(&["--line-numbers", "--title", "input"], &input);
+    let expected: Vec<_> = hexdump::Config::new().title("input").hexdump_iter(&input)
+        .enumerate()
+        .map(|(i, l)| format!("{} {}\n", i + 1, l))
+        .collect();
+    assert_eq!(output, expected.concat());
+}
+
+#[test]
+fn invalid_ruler_every() {
+    let output = Command::new(env!("CARGO_BIN_EXE_hexdump"))
+        .args(["--ruler-every", "0"])
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr).unwrap().starts_with("hexdump: the ruler"));
+}